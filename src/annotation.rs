@@ -62,6 +62,8 @@ pub struct Annotation {
     pub tracking_issue: String,
     pub feature: String,
     pub tags: BTreeSet<String>,
+    pub expires: Option<Date>,
+    pub metric: Option<u64>,
 }
 
 impl Annotation {
@@ -127,6 +129,71 @@ impl Annotation {
     }
 }
 
+/// A calendar date, stored as the number of days since the unix epoch
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct Date(i64);
+
+impl Date {
+    pub fn days(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromStr for Date {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let err = || anyhow!(format!("invalid date {:?}; expected YYYY-MM-DD", value));
+
+        let mut parts = value.splitn(3, '-');
+        let mut next = || -> Result<i64, Error> {
+            let part = parts.next().ok_or_else(err)?;
+            part.parse().map_err(|_| err())
+        };
+        let (year, month, day) = (next()?, next()?, next()?);
+
+        let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            2 if leap_year => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return Err(err()),
+        };
+
+        if !(1..=days_in_month).contains(&day) {
+            return Err(err());
+        }
+
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = if year >= 0 { year } else { year - 399 } / 400;
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        Ok(Self(era * 146_097 + day_of_era - 719_468))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let days = self.0 + 719_468;
+        let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub enum AnnotationType {
     Spec,
@@ -163,7 +230,7 @@ impl FromStr for AnnotationType {
         match v {
             "SPEC" | "spec" => Ok(Self::Spec),
             "TEST" | "test" => Ok(Self::Test),
-            "CITATION" | "citation" => Ok(Self::Citation),
+            "CITATION" | "citation" | "IMPLEMENTATION" | "implementation" => Ok(Self::Citation),
             "EXCEPTION" | "exception" => Ok(Self::Exception),
             "TODO" | "todo" => Ok(Self::Todo),
             "IMPLICATION" | "implication" => Ok(Self::Implication),
@@ -215,3 +282,33 @@ impl FromStr for AnnotationLevel {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_test() {
+        let days = |value: &str| value.parse::<Date>().unwrap().days();
+        assert_eq!(days("1970-01-01"), 0);
+        assert_eq!(days("2000-03-01"), 11_017);
+        assert_eq!(days("2025-12-31"), 20_453);
+        assert!("2025-13-01".parse::<Date>().is_err());
+        assert!("2099-02-31".parse::<Date>().is_err());
+        assert!("2100-02-29".parse::<Date>().is_err());
+        assert!("2025-04-31".parse::<Date>().is_err());
+        assert_eq!(days("2024-02-29"), 19_782);
+        assert!("2025-12".parse::<Date>().is_err());
+        assert!("tomorrow".parse::<Date>().is_err());
+
+        for value in [
+            "1970-01-01",
+            "2000-02-29",
+            "2000-03-01",
+            "2025-12-31",
+            "1969-07-20",
+        ] {
+            assert_eq!(value.parse::<Date>().unwrap().to_string(), value);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Arguments, Error};
+use structopt::{clap::Shell, StructOpt};
+
+#[derive(Debug, StructOpt)]
+pub struct Completions {
+    /// Shell to generate completions for
+    #[structopt(possible_values = &Shell::variants(), case_insensitive = true)]
+    shell: Shell,
+}
+
+impl Completions {
+    pub fn exec(&self) -> Result<(), Error> {
+        Arguments::clap().gen_completions_to("duvet", self.shell, &mut std::io::stdout());
+        Ok(())
+    }
+}
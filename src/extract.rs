@@ -4,15 +4,22 @@
 #![allow(clippy::trivial_regex)]
 
 use crate::{
-    annotation::AnnotationLevel,
+    annotation::{AnnotationLevel, AnnotationType},
+    project::Project,
     specification::{Format, Line, Section, Specification},
-    target::TargetPath,
+    target::{Fetch, TargetPath},
     Error,
 };
+use anyhow::anyhow;
 use lazy_static::lazy_static;
 use rayon::prelude::*;
 use regex::{Regex, RegexSet};
-use std::{fs::OpenOptions, io::BufWriter, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs::OpenOptions,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
 use structopt::StructOpt;
 
 #[cfg(test)]
@@ -63,20 +70,108 @@ pub struct Extract {
     #[structopt(long = "spec-path")]
     pub spec_path: Option<String>,
 
+    #[structopt(flatten)]
+    fetch: Fetch,
+
+    /// Prints annotation comments for the requirements in the given sections
+    /// instead of writing files
+    #[structopt(long = "emit-comments", number_of_values = 1)]
+    emit_comments: Vec<String>,
+
+    /// The annotation type used by `--emit-comments`
+    #[structopt(long = "comment-type", default_value = "citation")]
+    comment_type: AnnotationType,
+
+    /// Prints the requirements that were added, removed or changed compared to
+    /// an older version of the specification instead of writing files
+    #[structopt(long)]
+    compare: Option<TargetPath>,
+
+    /// Glob patterns for source files to check for citations of the compared requirements
+    ///
+    /// Removed or changed requirements that are referenced by an annotation are marked as
+    /// `(cited)` in the `--compare` output.
+    #[structopt(long = "source-pattern", number_of_values = 1)]
+    source_patterns: Vec<String>,
+
     target: TargetPath,
 }
 
 impl Extract {
     pub fn exec(&self) -> Result<(), Error> {
-        let contents = self.target.load(self.spec_path.as_deref())?;
+        let contents = self.target.load(self.spec_path.as_deref(), self.fetch)?;
         let spec = self.format.parse(&contents)?;
         let sections = extract_sections(&spec);
         let local_path = self.target.local(self.spec_path.as_deref());
 
-        if self.out.extension().is_some() {
+        if !self.emit_comments.is_empty() {
+            let stdout = std::io::stdout();
+            let mut stdout = BufWriter::new(stdout.lock());
+
+            for id in &self.emit_comments {
+                let (section, features) = sections
+                    .iter()
+                    .find(|(section, _)| &section.id == id)
+                    .ok_or_else(|| anyhow!("section {:?} has no requirements", id))?;
+
+                write_comments(
+                    &mut stdout,
+                    &self.target,
+                    section,
+                    features,
+                    self.comment_type,
+                )?;
+            }
+
+            stdout.flush()?;
+
+            return Ok(());
+        }
+
+        if let Some(previous_target) = &self.compare {
+            let previous = previous_target.load(self.spec_path.as_deref(), self.fetch)?;
+            let previous = self.format.parse(&previous)?;
+            let previous = extract_sections(&previous);
+            let cited = self.cited(previous_target)?;
+
+            let stdout = std::io::stdout();
+            let mut stdout = BufWriter::new(stdout.lock());
+            write_comparison(&mut stdout, &previous, &sections, &cited)?;
+            stdout.flush()?;
+
+            return Ok(());
+        }
+
+        if let Some(extension) = self.out.extension() {
             // assume a path with an extension is a single file
-            // TODO output to single file
-            todo!("single file not implemented");
+            if let Some(parent) = self.out.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            let file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&self.out)?;
+            let mut file = BufWriter::new(file);
+
+            let target = &self.target;
+
+            match extension.to_str() {
+                Some("rs") => {
+                    for (section, features) in &sections {
+                        write_rust(&mut file, target, section, features)?;
+                    }
+                }
+                Some("toml") => write_toml_file(&mut file, target, &sections)?,
+                Some("json") => write_json_file(&mut file, target, &sections)?,
+                _ => {
+                    return Err(anyhow!(
+                        "unsupported output extension {:?}",
+                        self.out.display()
+                    ))
+                }
+            }
         } else {
             // output to directory
             sections
@@ -104,6 +199,7 @@ impl Extract {
                     match &self.extension[..] {
                         "rs" => write_rust(&mut file, target, section, features)?,
                         "toml" => write_toml(&mut file, target, section, features)?,
+                        "json" => write_json(&mut file, target, section, features)?,
                         ext => unimplemented!("{}", ext),
                     }
 
@@ -213,12 +309,142 @@ impl<'a> Feature<'a> {
     }
 
     pub fn compound_level(&self) -> Option<AnnotationLevel> {
-        KEY_WORDS_SET
-            .matches(&self.quote.join("\n"))
+        quote_level(&self.quote.join("\n"))
+    }
+}
+
+/// Returns the highest requirement level of the key words in the quote, if any
+pub fn quote_level(quote: &str) -> Option<AnnotationLevel> {
+    KEY_WORDS_SET
+        .matches(quote)
+        .iter()
+        .map(|i| KEY_WORDS[i].1)
+        .max()
+}
+
+impl Extract {
+    /// Returns the sections and quotes of the requirements that are referenced by the
+    /// `--source-pattern` files, for either version of the specification
+    fn cited(&self, previous: &TargetPath) -> Result<Cited, Error> {
+        let mut sources = HashSet::new();
+        for pattern in &self.source_patterns {
+            Project::source_file(pattern, &[], &mut sources)?;
+        }
+
+        let targets = [canonical(&self.target), canonical(previous)];
+        let mut cited = Cited::new();
+
+        for source in &sources {
+            let (annotations, _lines) = source.annotations()?;
+            for annotation in annotations {
+                let section = match annotation.target_section() {
+                    Some(section) => section.to_string(),
+                    None => continue,
+                };
+
+                let target = TargetPath::from_annotation(&annotation)?;
+                if !targets.contains(&canonical(&target)) {
+                    continue;
+                }
+
+                cited.insert((section, normalize_whitespace(&annotation.quote)));
+            }
+        }
+
+        Ok(cited)
+    }
+}
+
+/// Requirements referenced from source files, keyed by section and quote
+type Cited = BTreeSet<(String, String)>;
+
+/// Resolves local paths so relative and absolute references to the same file are equal
+fn canonical(target: &TargetPath) -> TargetPath {
+    match target {
+        TargetPath::Path(path) => {
+            TargetPath::Path(std::fs::canonicalize(path).unwrap_or_else(|_| path.clone()))
+        }
+        TargetPath::Url(_) => target.clone(),
+    }
+}
+
+fn normalize_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Writes the requirement differences between two versions of a specification
+///
+/// Requirements are matched by section and quote, ignoring whitespace. A removed requirement is
+/// considered changed if its section gained a requirement with similar text. Removed and changed
+/// requirements that are still cited are marked so they can be updated.
+fn write_comparison<W: std::io::Write>(
+    w: &mut W,
+    previous: &[(&Section, Vec<Feature>)],
+    current: &[(&Section, Vec<Feature>)],
+    cited: &Cited,
+) -> Result<(), std::io::Error> {
+    fn requirements<'a>(
+        sections: &'a [(&Section, Vec<Feature>)],
+    ) -> BTreeMap<&'a str, BTreeSet<String>> {
+        sections
             .iter()
-            .map(|i| KEY_WORDS[i].1)
-            .max()
+            .map(|(section, features)| {
+                let quotes = features
+                    .iter()
+                    .map(|feature| normalize_whitespace(&feature.quote.join(" ")))
+                    .collect();
+                (&*section.id, quotes)
+            })
+            .collect()
     }
+
+    let previous = requirements(previous);
+    let current = requirements(current);
+    let empty = BTreeSet::new();
+
+    let ids: BTreeSet<_> = previous.keys().chain(current.keys()).collect();
+
+    for id in ids {
+        let before = previous.get(id).unwrap_or(&empty);
+        let after = current.get(id).unwrap_or(&empty);
+
+        let mut added: Vec<_> = after.difference(before).collect();
+
+        for quote in before.difference(after) {
+            let marker = if cited.contains(&(id.to_string(), quote.clone())) {
+                " (cited)"
+            } else {
+                ""
+            };
+
+            // allow roughly a third of the requirement to be reworded
+            let max_distance = (quote.len() as u32 / 3).max(2);
+            let changed = added
+                .iter()
+                .enumerate()
+                .map(|(idx, candidate)| {
+                    let distance =
+                        triple_accel::levenshtein(quote.as_bytes(), candidate.as_bytes());
+                    (distance, idx)
+                })
+                .filter(|(distance, _)| *distance <= max_distance)
+                .min();
+
+            if let Some((_, idx)) = changed {
+                let candidate = added.remove(idx);
+                writeln!(w, "~ {}: {:?}{}", id, quote, marker)?;
+                writeln!(w, "    => {:?}", candidate)?;
+            } else {
+                writeln!(w, "- {}: {:?}{}", id, quote, marker)?;
+            }
+        }
+
+        for quote in added {
+            writeln!(w, "+ {}: {:?}", id, quote)?;
+        }
+    }
+
+    Ok(())
 }
 
 fn find_open(lines: &[Line], lineno: usize, start: usize) -> (usize, usize) {
@@ -363,6 +589,43 @@ fn write_rust<W: std::io::Write>(
     Ok(())
 }
 
+/// The maximum width of emitted comment lines, including the prefix
+const COMMENT_WIDTH: usize = 80;
+
+fn write_comments<W: std::io::Write>(
+    w: &mut W,
+    target: &TargetPath,
+    section: &Section,
+    features: &[Feature],
+    anno: AnnotationType,
+) -> Result<(), std::io::Error> {
+    for feature in features {
+        writeln!(w, "//= {}#{}", target, section.id)?;
+        if anno != AnnotationType::Citation {
+            writeln!(w, "//= type={}", anno.to_string().to_lowercase())?;
+        }
+
+        let mut line = String::new();
+        for word in feature.quote.iter().flat_map(|l| l.split_whitespace()) {
+            if !line.is_empty() && "//# ".len() + line.len() + 1 + word.len() > COMMENT_WIDTH {
+                writeln!(w, "//# {}", line)?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            writeln!(w, "//# {}", line)?;
+        }
+
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
 fn write_toml<W: std::io::Write>(
     w: &mut W,
     target: &TargetPath,
@@ -393,3 +656,78 @@ fn write_toml<W: std::io::Write>(
 
     Ok(())
 }
+
+fn write_toml_file<W: std::io::Write>(
+    w: &mut W,
+    target: &TargetPath,
+    sections: &[(&Section, Vec<Feature>)],
+) -> Result<(), std::io::Error> {
+    for (section, features) in sections {
+        writeln!(w, "# {}", section.full_title)?;
+        writeln!(w)?;
+
+        for feature in features {
+            writeln!(w, "[[spec]]")?;
+            writeln!(w, "target = \"{}#{}\"", target, section.id)?;
+            writeln!(w, "level = \"{}\"", feature.level)?;
+            writeln!(w, "quote = '''")?;
+            for line in feature.quote.iter() {
+                writeln!(w, "{}", line)?;
+            }
+            writeln!(w, "'''")?;
+            writeln!(w)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_json<W: std::io::Write>(
+    w: &mut W,
+    target: &TargetPath,
+    section: &Section,
+    features: &[Feature],
+) -> Result<(), std::io::Error> {
+    use v_jsonescape::escape;
+
+    write!(w, "{{")?;
+    write!(
+        w,
+        r#""target":"{}#{}","#,
+        escape(&target.to_string()),
+        escape(&section.id)
+    )?;
+    write!(w, r#""title":"{}","#, escape(&section.title))?;
+    write!(w, r#""requirements":["#)?;
+    for (idx, feature) in features.iter().enumerate() {
+        if idx > 0 {
+            write!(w, ",")?;
+        }
+        write!(
+            w,
+            r#"{{"level":"{}","quote":"{}"}}"#,
+            feature.level,
+            escape(&feature.quote.join(" "))
+        )?;
+    }
+    write!(w, "]}}")?;
+
+    Ok(())
+}
+
+fn write_json_file<W: std::io::Write>(
+    w: &mut W,
+    target: &TargetPath,
+    sections: &[(&Section, Vec<Feature>)],
+) -> Result<(), std::io::Error> {
+    write!(w, "[")?;
+    for (idx, (section, features)) in sections.iter().enumerate() {
+        if idx > 0 {
+            write!(w, ",")?;
+        }
+        write_json(w, target, section, features)?;
+    }
+    writeln!(w, "]")?;
+
+    Ok(())
+}
@@ -40,3 +40,72 @@ snapshot_test!(esdk_client, ".md");
 snapshot_test!(esdk_decrypt, ".md");
 snapshot_test!(esdk_encrypt, ".md");
 snapshot_test!(esdk_streaming, ".md");
+
+#[test]
+fn emit_comments() {
+    let contents = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/specs/esdk_client.md"));
+
+    let spec = Format::Auto.parse(contents).unwrap();
+    let sections = extract_sections(&spec);
+    let (section, features) = &sections[0];
+    let target: TargetPath = "https://example.com/client.md".parse().unwrap();
+
+    let mut out = vec![];
+    write_comments(&mut out, &target, section, features, AnnotationType::Test).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    let header = format!("//= https://example.com/client.md#{}", section.id);
+    assert!(out.starts_with(&header));
+    assert!(out.contains("//= type=test\n"));
+    assert!(out
+        .lines()
+        .filter(|line| line.starts_with("//#"))
+        // words longer than the width can't be wrapped
+        .all(|line| line.len() <= COMMENT_WIDTH || !line[4..].contains(' ')));
+}
+
+#[test]
+fn compare() {
+    let previous = r#"
+# Spec
+
+## Handshake
+
+Clients MUST send a hello. Servers MUST reply with a hello.
+
+## Transport
+
+Endpoints MAY retry.
+"#;
+    let current = r#"
+# Spec
+
+## Handshake
+
+Clients MUST send a hello. Servers MUST reply with a hello message.
+
+## Transport
+
+Endpoints SHOULD log errors.
+"#;
+
+    let previous = Format::Markdown.parse(previous).unwrap();
+    let current = Format::Markdown.parse(current).unwrap();
+
+    let cited = [("transport", "Endpoints MAY retry.")]
+        .iter()
+        .map(|(section, quote)| (section.to_string(), quote.to_string()))
+        .collect();
+
+    let mut out = vec![];
+    write_comparison(
+        &mut out,
+        &extract_sections(&previous),
+        &extract_sections(&current),
+        &cited,
+    )
+    .unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    insta::assert_snapshot!(out);
+}
@@ -1,14 +1,18 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::anyhow;
 use structopt::StructOpt;
 
 mod annotation;
+mod completions;
 mod extract;
+mod man;
 mod parser;
 mod pattern;
 mod project;
 mod report;
+mod schema;
 mod source;
 mod sourcemap;
 mod specification;
@@ -22,23 +26,57 @@ pub use anyhow::Error;
 
 fn main() {
     if let Err(err) = Arguments::from_args().exec() {
-        eprintln!("{}", err);
+        // print the full chain of error contexts, without the backtrace
+        eprintln!("{:#}", err);
         std::process::exit(1);
     }
 }
 
+#[derive(Debug, StructOpt)]
+struct Arguments {
+    /// Fails if this version of duvet doesn't satisfy the requirement, e.g. `>=0.3, <0.4`
+    #[structopt(long = "require-version", env = "DUVET_REQUIRE_VERSION", global = true)]
+    require_version: Option<semver::VersionReq>,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+impl Arguments {
+    pub fn exec(&self) -> Result<(), Error> {
+        if let Some(req) = &self.require_version {
+            let current = env!("CARGO_PKG_VERSION");
+            if !req.matches(&semver::Version::parse(current)?) {
+                return Err(anyhow!(format!(
+                    "duvet {} does not satisfy the required version {:?}",
+                    current,
+                    req.to_string()
+                )));
+            }
+        }
+
+        self.command.exec()
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, StructOpt)]
-enum Arguments {
+enum Command {
     Extract(extract::Extract),
     Report(report::Report),
+    Schema(schema::Schema),
+    Completions(completions::Completions),
+    Man(man::Man),
 }
 
-impl Arguments {
+impl Command {
     pub fn exec(&self) -> Result<(), Error> {
         match self {
             Self::Extract(args) => args.exec(),
             Self::Report(args) => args.exec(),
+            Self::Schema(args) => args.exec(),
+            Self::Completions(args) => args.exec(),
+            Self::Man(args) => args.exec(),
         }
     }
 }
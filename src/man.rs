@@ -0,0 +1,86 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{extract::Extract, report::Report, schema::Schema, Arguments, Error};
+use std::{
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+use structopt::{clap::App, StructOpt};
+
+#[derive(Debug, StructOpt)]
+pub struct Man {
+    /// Writes the man page to the given path instead of stdout
+    #[structopt(short, long)]
+    out: Option<PathBuf>,
+}
+
+impl Man {
+    pub fn exec(&self) -> Result<(), Error> {
+        let mut page = vec![];
+        write_page(&mut page)?;
+
+        if let Some(out) = &self.out {
+            if let Some(parent) = out.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(out, page)?;
+        } else {
+            std::io::stdout().write_all(&page)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_page<W: Write>(w: &mut W) -> Result<(), Error> {
+    let mut w = BufWriter::new(w);
+
+    writeln!(
+        w,
+        ".TH DUVET 1 \"\" \"duvet {}\"",
+        env!("CARGO_PKG_VERSION")
+    )?;
+    writeln!(w, ".SH NAME")?;
+    writeln!(w, "duvet \\- {}", env!("CARGO_PKG_DESCRIPTION"))?;
+    writeln!(w, ".SH SYNOPSIS")?;
+    writeln!(w, ".B duvet")?;
+    writeln!(w, "<SUBCOMMAND> [OPTIONS]")?;
+
+    let subcommands = [
+        ("extract", Extract::clap()),
+        ("report", Report::clap()),
+        ("schema", Schema::clap()),
+    ];
+
+    write_help(&mut w, "DESCRIPTION", Arguments::clap())?;
+
+    for (name, app) in subcommands {
+        let title = format!("DUVET {}", name.to_uppercase());
+        write_help(&mut w, &title, app.name(format!("duvet {}", name)))?;
+    }
+
+    w.flush()?;
+
+    Ok(())
+}
+
+fn write_help<W: Write>(w: &mut W, title: &str, mut app: App) -> Result<(), Error> {
+    let mut help = vec![];
+    app.write_long_help(&mut help)?;
+    let help = String::from_utf8(help)?;
+
+    writeln!(w, ".SH \"{}\"", title)?;
+    writeln!(w, ".nf")?;
+    for line in help.lines() {
+        let line = line.replace('\\', "\\e");
+        // lines starting with control characters need to be escaped
+        if line.starts_with('.') || line.starts_with('\'') {
+            write!(w, "\\&")?;
+        }
+        writeln!(w, "{}", line)?;
+    }
+    writeln!(w, ".fi")?;
+
+    Ok(())
+}
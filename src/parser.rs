@@ -114,6 +114,8 @@ impl<'a> From<ParsedAnnotation<'a>> for Annotation {
             format: a.format,
             feature: a.feature.to_string(),
             tags: Default::default(),
+            expires: None,
+            metric: None,
             tracking_issue: a.tracking_issue.to_string(),
         }
     }
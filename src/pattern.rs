@@ -2,17 +2,55 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    annotation::{Annotation, AnnotationSet, AnnotationType},
+    annotation::{Annotation, AnnotationSet, AnnotationType, Date},
     parser::ParsedAnnotation,
     sourcemap::{LinesIter, Str},
     Error,
 };
 use anyhow::anyhow;
-use std::path::Path;
+use std::{collections::BTreeSet, path::Path};
 
 #[cfg(test)]
 mod tests;
 
+/// Returns the name of the language of the file, based on its extension
+pub fn language(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+
+    match extension {
+        "rs" => "Rust",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => "C++",
+        "cs" => "C#",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "proto" => "Protobuf",
+        "py" | "pyi" => "Python",
+        "sh" | "bash" | "zsh" => "Shell",
+        "rb" => "Ruby",
+        "pl" => "Perl",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "cmake" => "CMake",
+        "r" => "R",
+        "sql" => "SQL",
+        "lua" => "Lua",
+        "hs" => "Haskell",
+        "lisp" => "Lisp",
+        "el" => "Emacs Lisp",
+        "clj" => "Clojure",
+        "scm" => "Scheme",
+        _ => "Other",
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub struct Pattern<'a> {
     meta: &'a str,
@@ -29,6 +67,31 @@ impl<'a> Default for Pattern<'a> {
 }
 
 impl<'a> Pattern<'a> {
+    /// Returns the comment preset for the language of the file
+    ///
+    /// Files without a known extension use the default `//=`/`//#` pattern.
+    pub fn from_path(path: &Path) -> Self {
+        match language(path) {
+            // Python, shell, Ruby, Perl and configuration languages
+            "Python" | "Shell" | "Ruby" | "Perl" | "TOML" | "YAML" | "CMake" | "R" => Self {
+                meta: "#=",
+                content: "##",
+            },
+            // SQL, Lua and Haskell
+            "SQL" | "Lua" | "Haskell" => Self {
+                meta: "--=",
+                content: "--#",
+            },
+            // Lisp dialects
+            "Lisp" | "Emacs Lisp" | "Clojure" | "Scheme" => Self {
+                meta: ";;=",
+                content: ";;#",
+            },
+            // C-like languages (C/C++, Go, Java, JS/TS, Protobuf, ...) use the default
+            _ => Self::default(),
+        }
+    }
+
     pub fn from_arg(arg: &'a str) -> Result<Self, Error> {
         let mut parts = arg.split(',').filter(|p| !p.is_empty());
         let meta = parts.next().expect("should have at least one pattern");
@@ -62,14 +125,23 @@ impl<'a> Pattern<'a> {
     }
 
     fn try_meta<'b>(&self, line: &'b str) -> Option<&'b str> {
-        line.strip_prefix(self.meta)
+        strip_doc_comment(line).strip_prefix(self.meta)
     }
 
     fn try_content<'b>(&self, line: &'b str) -> Option<&'b str> {
-        line.strip_prefix(self.content)
+        strip_doc_comment(line).strip_prefix(self.content)
     }
 }
 
+/// Allows annotations to be written inside of Rust doc comments
+/// (`/// //= ...` or `//! //= ...`) so they can be rendered by rustdoc
+fn strip_doc_comment(line: &str) -> &str {
+    line.strip_prefix("///")
+        .or_else(|| line.strip_prefix("//!"))
+        .map(str::trim_start)
+        .unwrap_or(line)
+}
+
 enum ParserState<'a> {
     Search,
     CapturingMeta(Capture<'a>),
@@ -132,10 +204,16 @@ impl<'a> ParserState<'a> {
     }
 }
 
+const META_KEYS: &str =
+    "source, level, format, type, reason, feature, tracking-issue, tag, expires, metric";
+
 #[derive(Debug)]
 struct Capture<'a> {
     contents: String,
     annotation: ParsedAnnotation<'a>,
+    tags: BTreeSet<String>,
+    expires: Option<Date>,
+    metric: Option<u64>,
 }
 
 impl<'a> Capture<'a> {
@@ -149,6 +227,9 @@ impl<'a> Capture<'a> {
                 item_column: column as _,
                 ..Default::default()
             },
+            tags: BTreeSet::new(),
+            expires: None,
+            metric: None,
         }
     }
 
@@ -163,16 +244,33 @@ impl<'a> Capture<'a> {
             ("level", Some(value)) => self.annotation.level = value.parse()?,
             ("format", Some(value)) => self.annotation.format = value.parse()?,
             ("type", Some(value)) => self.annotation.anno = value.parse()?,
-            ("reason", Some(value)) if self.annotation.anno == AnnotationType::Exception => {
-                self.annotation.comment = value
+            // fields that depend on the type are validated once all of the metadata is
+            // captured so the keys can be specified in any order
+            ("reason", Some(value)) => self.annotation.comment = value,
+            ("feature", Some(value)) => self.annotation.feature = value,
+            ("tracking-issue", Some(value)) => self.annotation.tracking_issue = value,
+            ("tag", Some(value)) => {
+                self.tags.insert(value.to_string());
+            }
+            ("metric", Some(_)) if self.metric.is_some() => {
+                return Err(anyhow!("metric already specified"))
             }
-            ("feature", Some(value)) if self.annotation.anno == AnnotationType::Todo => {
-                self.annotation.feature = value
+            ("metric", Some(value)) => {
+                let metric = value
+                    .parse()
+                    .map_err(|_| anyhow!(format!("invalid metric {:?}", value)))?;
+                self.metric = Some(metric);
             }
-            ("tracking-issue", Some(value)) if self.annotation.anno == AnnotationType::Todo => {
-                self.annotation.tracking_issue = value
+            ("expires", Some(_)) if self.expires.is_some() => {
+                return Err(anyhow!("expires already specified"))
+            }
+            ("expires", Some(value)) => self.expires = Some(value.parse()?),
+            (key, Some(_)) => {
+                return Err(anyhow!(format!(
+                    "invalid metadata field {}; expected one of {}",
+                    key, META_KEYS
+                )))
             }
-            (key, Some(_)) => return Err(anyhow!(format!("invalid metadata field {}", key))),
             (value, None) if self.annotation.target.is_empty() => self.annotation.target = value,
             (_, None) => return Err(anyhow!("annotation source already specified")),
         }
@@ -195,6 +293,9 @@ impl<'a> Capture<'a> {
             source: path.into(),
             quote: self.contents,
             manifest_dir: std::env::current_dir()?,
+            tags: self.tags,
+            expires: self.expires,
+            metric: self.metric,
             ..self.annotation.into()
         };
 
@@ -206,6 +307,29 @@ impl<'a> Capture<'a> {
             return Err(anyhow!("missing source information"));
         }
 
+        if !annotation.comment.is_empty()
+            && !matches!(
+                annotation.anno,
+                AnnotationType::Exception | AnnotationType::Todo
+            )
+        {
+            return Err(anyhow!(
+                "reason is only valid for exception and todo annotations"
+            ));
+        }
+
+        if (!annotation.feature.is_empty() || !annotation.tracking_issue.is_empty())
+            && annotation.anno != AnnotationType::Todo
+        {
+            return Err(anyhow!(
+                "feature and tracking-issue are only valid for todo annotations"
+            ));
+        }
+
+        if annotation.expires.is_some() && annotation.anno != AnnotationType::Exception {
+            return Err(anyhow!("expires is only valid for exception annotations"));
+        }
+
         Ok(annotation)
     }
 }
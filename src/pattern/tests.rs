@@ -92,3 +92,87 @@ snapshot!(
     //= https://example.com/spec.txt
     //# Here is my citation"#
 );
+
+snapshot!(
+    doc_comment,
+    r#"
+    /// Performs the handshake
+    ///
+    /// # Compliance
+    ///
+    /// //= https://example.com/spec.txt
+    /// //= type=test
+    /// //# Here is my citation
+    fn handshake() {}
+    "#
+);
+
+#[test]
+fn language_presets() {
+    let python = Pattern::from_path(Path::new("src/client.py"));
+    let mut annotations = Default::default();
+    python
+        .extract(
+            r#"
+            #= https://example.com/spec.txt
+            #= type=test
+            ## Here is my citation
+            def test_citation():
+                pass
+            "#,
+            Path::new("src/client.py"),
+            &mut annotations,
+        )
+        .unwrap();
+    assert_eq!(annotations.len(), 1);
+
+    assert_eq!(
+        Pattern::from_path(Path::new("schema.sql")),
+        Pattern::from_arg("--=,--#").unwrap()
+    );
+    assert_eq!(Pattern::from_path(Path::new("main.go")), Pattern::default());
+    assert_eq!(
+        Pattern::from_path(Path::new("Makefile")),
+        Pattern::default()
+    );
+}
+
+snapshot!(
+    meta_any_order,
+    r#"
+    //= https://example.com/spec.txt
+    //= reason=This isn't possible currently
+    //= type=exception
+    //# Here is my citation
+    "#
+);
+
+snapshot!(
+    type_implementation,
+    r#"
+    //= https://example.com/spec.txt
+    //= type=implementation
+    //= tag=networking
+    //= tag=parser
+    //# Here is my citation
+    "#
+);
+
+snapshot!(
+    invalid_reason,
+    r#"
+    //= https://example.com/spec.txt
+    //= type=test
+    //= reason=Tests don't have reasons
+    //# Here is my citation
+    "#
+);
+
+snapshot!(
+    unknown_meta,
+    r#"
+    //= https://example.com/spec.txt
+    //= owner=someone
+    //# Here is my citation
+    "#
+);
@@ -1,8 +1,8 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{pattern::Pattern, source::SourceFile, Error};
-use glob::glob;
+use crate::{pattern::Pattern, source::SourceFile, target::Fetch, Error};
+use glob::{glob, Pattern as Glob};
 use std::collections::HashSet;
 use structopt::StructOpt;
 
@@ -49,6 +49,9 @@ pub struct Project {
     manifest_path: Option<String>,
 
     /// Glob patterns for additional source files
+    ///
+    /// The annotation comment style is picked from the file extension unless the
+    /// pattern is prefixed with an explicit style, e.g. `(#=,##)scripts/**/*.sh`.
     #[structopt(long = "source-pattern")]
     source_patterns: Vec<String>,
 
@@ -56,6 +59,10 @@ pub struct Project {
     #[structopt(long = "spec-pattern")]
     spec_patterns: Vec<String>,
 
+    /// Glob patterns for source files that should not be scanned for annotations
+    #[structopt(long = "exclude-pattern")]
+    exclude_patterns: Vec<String>,
+
     /// Path to store the collection of spec files
     ///
     /// The collection of spec files are stored in a folder called `specs`. The
@@ -63,14 +70,32 @@ pub struct Project {
     /// argument to override the default location.
     #[structopt(long = "spec-path")]
     pub spec_path: Option<String>,
+
+    /// Only print errors
+    #[structopt(long, short = "q")]
+    pub quiet: bool,
+
+    #[structopt(flatten)]
+    pub fetch: Fetch,
 }
 
 impl Project {
     pub fn sources(&self) -> Result<HashSet<SourceFile>, Error> {
         let mut sources = HashSet::new();
 
+        let excludes = self
+            .exclude_patterns
+            .iter()
+            .map(|pattern| Glob::new(pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut skipped = 0;
+
         for pattern in &self.source_patterns {
-            self.source_file(pattern, &mut sources)?;
+            skipped += Self::source_file(pattern, &excludes, &mut sources)?;
+        }
+
+        if skipped > 0 && !self.quiet {
+            eprintln!("{} files skipped by --exclude-pattern", skipped);
         }
 
         for pattern in &self.spec_patterns {
@@ -80,11 +105,13 @@ impl Project {
         Ok(sources)
     }
 
-    fn source_file<'a>(
-        &self,
+    /// Adds the files matching a `--source-pattern` to the set, returning the number of excluded
+    /// files
+    pub fn source_file<'a>(
         pattern: &'a str,
+        excludes: &[Glob],
         files: &mut HashSet<SourceFile<'a>>,
-    ) -> Result<(), Error> {
+    ) -> Result<usize, Error> {
         let (compliance_pattern, file_pattern) = if let Some(pattern) = pattern.strip_prefix('(') {
             let mut parts = pattern.splitn(2, ')');
             let pattern = parts.next().expect("invalid pattern");
@@ -92,16 +119,27 @@ impl Project {
 
             let pattern = Pattern::from_arg(pattern)?;
 
-            (pattern, file_pattern)
+            (Some(pattern), file_pattern)
         } else {
-            (Pattern::default(), pattern)
+            // pick a comment style based on the file extension
+            (None, pattern)
         };
 
+        let mut skipped = 0;
+
         for entry in glob(file_pattern)? {
-            files.insert(SourceFile::Text(compliance_pattern, entry?));
+            let entry = entry?;
+
+            if excludes.iter().any(|exclude| exclude.matches_path(&entry)) {
+                skipped += 1;
+                continue;
+            }
+
+            let pattern = compliance_pattern.unwrap_or_else(|| Pattern::from_path(&entry));
+            files.insert(SourceFile::Text(pattern, entry));
         }
 
-        Ok(())
+        Ok(skipped)
     }
 
     fn spec_file<'a>(
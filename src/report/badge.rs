@@ -0,0 +1,97 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{summary::Summary, ReportResult};
+use std::{
+    fs::File,
+    io::{BufWriter, Error, Write},
+    path::Path,
+};
+
+const LABEL: &str = "compliance";
+
+/// Approximate width of a character in the badge font, in pixels
+const CHAR_WIDTH: usize = 7;
+
+/// Horizontal padding on each side of the badge text, in pixels
+const PADDING: usize = 6;
+
+/// Writes a `badge.svg` with the percentage of fulfilled requirements, along with a
+/// `badge.json` that can be used as a shields.io endpoint
+///
+/// Reports without any requirements show `n/a` rather than a percentage.
+pub fn report(report: &ReportResult, dir: &Path) -> Result<(), Error> {
+    std::fs::create_dir_all(dir)?;
+
+    let coverage = Summary::from_report(report).fulfilled_coverage();
+    let message = coverage.map_or_else(|| "n/a".to_string(), |c| format!("{:.0}%", c.floor()));
+    let color = color(coverage);
+
+    let mut file = BufWriter::new(File::create(dir.join("badge.svg"))?);
+    write_svg(&mut file, &message, color.1)?;
+    file.flush()?;
+
+    let mut file = BufWriter::new(File::create(dir.join("badge.json"))?);
+    writeln!(
+        file,
+        r#"{{"schemaVersion":1,"label":"{}","message":"{}","color":"{}"}}"#,
+        LABEL, message, color.0
+    )?;
+    file.flush()
+}
+
+/// Returns the shields.io color name and hex value for the coverage percentage
+fn color(coverage: Option<f64>) -> (&'static str, &'static str) {
+    let coverage = match coverage {
+        Some(coverage) => coverage,
+        None => return ("lightgrey", "#9f9f9f"),
+    };
+
+    if coverage >= 90.0 {
+        ("brightgreen", "#4c1")
+    } else if coverage >= 75.0 {
+        ("green", "#97ca00")
+    } else if coverage >= 50.0 {
+        ("yellow", "#dfb317")
+    } else {
+        ("red", "#e05d44")
+    }
+}
+
+fn write_svg<W: Write>(w: &mut W, message: &str, color: &str) -> Result<(), Error> {
+    let label_width = LABEL.len() * CHAR_WIDTH + PADDING * 2;
+    let message_width = message.len() * CHAR_WIDTH + PADDING * 2;
+    let width = label_width + message_width;
+
+    writeln!(
+        w,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{LABEL}: {message}">"#
+    )?;
+    writeln!(w, r#"  <title>{LABEL}: {message}</title>"#)?;
+    writeln!(
+        w,
+        r##"  <rect width="{label_width}" height="20" fill="#555"/>"##
+    )?;
+    writeln!(
+        w,
+        r#"  <rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>"#
+    )?;
+    writeln!(
+        w,
+        r##"  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##
+    )?;
+    writeln!(
+        w,
+        r#"    <text x="{}" y="14">{LABEL}</text>"#,
+        label_width / 2
+    )?;
+    writeln!(
+        w,
+        r#"    <text x="{}" y="14">{message}</text>"#,
+        label_width + message_width / 2
+    )?;
+    writeln!(w, "  </g>")?;
+    writeln!(w, "</svg>")?;
+
+    Ok(())
+}
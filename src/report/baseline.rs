@@ -0,0 +1,167 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::ReportResult;
+use crate::{annotation::AnnotationType, Error};
+use anyhow::{anyhow, Context};
+use core::fmt;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
+
+/// A subset of the `--json` report needed to compare against a baseline
+#[derive(Debug, Deserialize)]
+struct Snapshot {
+    annotations: Vec<SnapshotAnnotation>,
+    #[serde(default)]
+    statuses: HashMap<usize, SnapshotStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotAnnotation {
+    target_path: String,
+    target_section: Option<String>,
+    #[serde(rename = "type", default)]
+    anno: Option<String>,
+    #[serde(default)]
+    quote: String,
+}
+
+impl SnapshotAnnotation {
+    fn key(&self) -> Key {
+        Key {
+            target: self.target_path.clone(),
+            section: self.target_section.clone(),
+            quote: self.quote.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotStatus {
+    #[serde(default)]
+    incomplete: usize,
+}
+
+/// Identifies an annotation across reports, independent of where it is located in the source
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    target: String,
+    section: Option<String>,
+    quote: String,
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.target)?;
+        if let Some(section) = &self.section {
+            write!(f, "#{}", section)?;
+        }
+        write!(f, " - {:?}", self.quote)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Requirements {
+    incomplete: BTreeSet<Key>,
+    exceptions: BTreeSet<Key>,
+    todos: BTreeSet<Key>,
+}
+
+impl Requirements {
+    fn from_snapshot(snapshot: &Snapshot) -> Result<Self, Error> {
+        let mut requirements = Self::default();
+
+        for annotation in &snapshot.annotations {
+            match annotation.anno.as_deref() {
+                Some("EXCEPTION") => requirements.exceptions.insert(annotation.key()),
+                Some("TODO") => requirements.todos.insert(annotation.key()),
+                _ => continue,
+            };
+        }
+
+        for (id, status) in &snapshot.statuses {
+            let annotation = snapshot
+                .annotations
+                .get(*id)
+                .ok_or_else(|| anyhow!(format!("status references missing annotation {}", id)))?;
+
+            if status.incomplete > 0 {
+                requirements.incomplete.insert(annotation.key());
+            }
+        }
+
+        Ok(requirements)
+    }
+
+    fn from_report(report: &ReportResult) -> Self {
+        let mut requirements = Self::default();
+        let annotations: Vec<_> = report.annotations.iter().collect();
+
+        let key = |id: usize| {
+            let annotation = annotations[id];
+            Key {
+                target: annotation.resolve_target_path(),
+                section: annotation.target_section().map(String::from),
+                quote: annotation.quote.clone(),
+            }
+        };
+
+        for (id, annotation) in annotations.iter().enumerate() {
+            match annotation.anno {
+                AnnotationType::Exception => requirements.exceptions.insert(key(id)),
+                AnnotationType::Todo => requirements.todos.insert(key(id)),
+                _ => continue,
+            };
+        }
+
+        for target in report.targets.values() {
+            for (id, status) in target.statuses.iter() {
+                if status.incomplete > 0 {
+                    requirements.incomplete.insert(key(*id));
+                }
+            }
+        }
+
+        requirements
+    }
+}
+
+pub fn report(report: &ReportResult, baseline: &Path, quiet: bool) -> Result<(), Error> {
+    let snapshot = std::fs::read_to_string(baseline)
+        .with_context(|| format!("could not read baseline {}", baseline.display()))?;
+    let snapshot: Snapshot = serde_json::from_str(&snapshot)
+        .with_context(|| format!("could not parse baseline {}", baseline.display()))?;
+
+    let previous = Requirements::from_snapshot(&snapshot)?;
+    let current = Requirements::from_report(report);
+
+    let mut regressions = 0;
+
+    for key in current.incomplete.difference(&previous.incomplete) {
+        println!("newly uncovered requirement: {}", key);
+        regressions += 1;
+    }
+
+    for key in current.exceptions.difference(&previous.exceptions) {
+        println!("new exception: {}", key);
+        regressions += 1;
+    }
+
+    if !quiet {
+        for key in previous.todos.difference(&current.todos) {
+            println!("resolved todo: {}", key);
+        }
+    }
+
+    if regressions > 0 {
+        return Err(anyhow!(format!(
+            "{} regressions were found compared to the baseline",
+            regressions
+        )));
+    }
+
+    Ok(())
+}
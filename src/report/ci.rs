@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{ReportResult, TargetReport};
-use crate::annotation::AnnotationType;
+use crate::{annotation::AnnotationType, specification::Line};
 use anyhow::anyhow;
 use rayon::prelude::*;
 use std::collections::HashSet;
@@ -49,19 +49,27 @@ pub fn enforce_source(report: &TargetReport) -> Result<(), anyhow::Error> {
 
     if report.require_citations {
         // Significant lines are not cited.
-        if significant_lines.difference(&cited_lines).next().is_some() {
-            return Err(anyhow!("Specification requirements missing citation."));
+        if let Some(line) = significant_lines.difference(&cited_lines).next() {
+            return Err(missing(
+                report,
+                *line,
+                "Specification requirements missing citation.",
+            ));
         }
         // Citations that have no significance.
-        if cited_lines.difference(&significant_lines).next().is_some() {
-            return Err(anyhow!("Citation for non-existing specification."));
+        if let Some(line) = cited_lines.difference(&significant_lines).next() {
+            return Err(missing(
+                report,
+                *line,
+                "Citation for non-existing specification.",
+            ));
         }
     }
 
     if report.require_tests {
         // Cited lines without tests
-        if cited_lines.difference(&tested_lines).next().is_some() {
-            return Err(anyhow!("Citation missing test."));
+        if let Some(line) = cited_lines.difference(&tested_lines).next() {
+            return Err(missing(report, *line, "Citation missing test."));
         }
 
         // Tests without citation
@@ -72,3 +80,24 @@ pub fn enforce_source(report: &TargetReport) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+/// Includes the spec excerpt for the given line so the finding can be understood without
+/// opening the specification
+fn missing(report: &TargetReport, line: usize, message: &str) -> anyhow::Error {
+    for section in report.specification.sections.values() {
+        for l in &section.lines {
+            if let Line::Str(l) = l {
+                if l.line == line {
+                    return anyhow!(format!(
+                        "{}\n  --> {}\n   | {}",
+                        message,
+                        section.full_title,
+                        l.trim()
+                    ));
+                }
+            }
+        }
+    }
+
+    anyhow!(message.to_string())
+}
@@ -0,0 +1,138 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{ReportResult, TargetReport};
+use crate::annotation::{Annotation, AnnotationType};
+use rayon::prelude::*;
+use std::{
+    fs::File,
+    io::{BufWriter, Error, Write},
+    path::Path,
+};
+use v_jsonescape::escape;
+
+/// Writes a Markdown and a JSON evidence record for every fulfilled requirement
+pub fn report(report: &ReportResult, dir: &Path) -> Result<(), Error> {
+    std::fs::create_dir_all(dir)?;
+
+    let annotations: Vec<_> = report.annotations.iter().collect();
+
+    report
+        .targets
+        .par_iter()
+        .map(|(_source, target)| report_target(target, &annotations, dir))
+        .collect::<Result<(), Error>>()
+}
+
+fn report_target(
+    report: &TargetReport,
+    annotations: &[&Annotation],
+    dir: &Path,
+) -> Result<(), Error> {
+    for (anno_id, status) in report.statuses.iter() {
+        // only fulfilled requirements have evidence
+        if status.incomplete > 0 {
+            continue;
+        }
+
+        let requirement = annotations[*anno_id];
+        let evidence: Vec<_> = status
+            .related
+            .iter()
+            .map(|id| annotations[*id])
+            .filter(|anno| anno.anno != AnnotationType::Spec)
+            .collect();
+
+        let section = requirement.target_section().unwrap_or_default();
+        let name = format!("{}-{}", slug::slugify(section), anno_id);
+
+        let mut file = BufWriter::new(File::create(dir.join(format!("{}.md", name)))?);
+        write_markdown(&mut file, requirement, &evidence)?;
+        file.flush()?;
+
+        let mut file = BufWriter::new(File::create(dir.join(format!("{}.json", name)))?);
+        write_json(&mut file, requirement, &evidence)?;
+        file.flush()?;
+    }
+
+    Ok(())
+}
+
+fn write_markdown<W: Write>(
+    w: &mut W,
+    requirement: &Annotation,
+    evidence: &[&Annotation],
+) -> Result<(), Error> {
+    writeln!(w, "# {}", requirement.target)?;
+    writeln!(w)?;
+    writeln!(w, "**Level:** {}", requirement.level)?;
+    writeln!(w)?;
+    writeln!(w, "> {}", requirement.quote)?;
+
+    let groups = [
+        ("Citations", AnnotationType::Citation),
+        ("Tests", AnnotationType::Test),
+        ("Implications", AnnotationType::Implication),
+        ("Exceptions", AnnotationType::Exception),
+    ];
+
+    for (title, anno_type) in groups {
+        let mut entries = evidence
+            .iter()
+            .filter(|anno| anno.anno == anno_type)
+            .peekable();
+
+        if entries.peek().is_none() {
+            continue;
+        }
+
+        writeln!(w)?;
+        writeln!(w, "## {}", title)?;
+        writeln!(w)?;
+
+        for anno in entries {
+            writeln!(w, "* `{}:{}`", anno.source.display(), anno.anno_line)?;
+            if !anno.quote.is_empty() {
+                writeln!(w, "  > {}", anno.quote)?;
+            }
+            if !anno.comment.is_empty() {
+                writeln!(w, "  Reason: {}", anno.comment)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    w: &mut W,
+    requirement: &Annotation,
+    evidence: &[&Annotation],
+) -> Result<(), Error> {
+    write!(w, "{{")?;
+    write!(w, r#""target":"{}","#, escape(&requirement.target))?;
+    write!(w, r#""level":"{}","#, requirement.level)?;
+    write!(w, r#""quote":"{}","#, escape(&requirement.quote))?;
+    write!(w, r#""evidence":["#)?;
+    for (idx, anno) in evidence.iter().enumerate() {
+        if idx > 0 {
+            write!(w, ",")?;
+        }
+        write!(w, "{{")?;
+        write!(w, r#""type":"{}","#, anno.anno)?;
+        write!(
+            w,
+            r#""source":"{}","#,
+            escape(&anno.source.to_string_lossy())
+        )?;
+        write!(w, r#""line":{},"#, anno.anno_line)?;
+        write!(w, r#""quote":"{}""#, escape(&anno.quote))?;
+        if !anno.comment.is_empty() {
+            write!(w, r#","comment":"{}""#, escape(&anno.comment))?;
+        }
+        write!(w, "}}")?;
+    }
+    writeln!(w, "]}}")?;
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::ReportResult;
+use crate::annotation::{Annotation, AnnotationType};
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{BufWriter, Error, Write},
+    path::Path,
+};
+
+/// The maximum number of characters of a requirement to display in a node
+const QUOTE_LEN: usize = 60;
+
+/// Writes a GraphViz graph of specifications, requirements, annotations and sources
+///
+/// Annotations that aren't related to any requirement are connected directly to their
+/// specification so isolated clusters are still visible.
+pub fn report(report: &ReportResult, file: &Path) -> Result<(), Error> {
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut file = BufWriter::new(File::create(file)?);
+    report_writer(report, &mut file)?;
+    file.flush()
+}
+
+pub fn report_writer<Output: Write>(report: &ReportResult, w: &mut Output) -> Result<(), Error> {
+    let annotations: Vec<_> = report.annotations.iter().collect();
+    let mut sources = BTreeSet::new();
+
+    writeln!(w, "digraph duvet {{")?;
+    writeln!(w, "  rankdir=LR;")?;
+
+    for (target, target_report) in &report.targets {
+        let spec = format!("spec:{}", target.path);
+        writeln!(
+            w,
+            "  {:?} [label={:?}, shape=folder];",
+            spec,
+            target.path.to_string()
+        )?;
+
+        let mut related = BTreeSet::new();
+
+        for (anno_id, status) in target_report.statuses.iter() {
+            let requirement = annotations[*anno_id];
+            writeln!(
+                w,
+                "  \"anno:{}\" [label={:?}, shape=box];",
+                anno_id,
+                requirement_label(requirement)
+            )?;
+            writeln!(w, "  {:?} -> \"anno:{}\";", spec, anno_id)?;
+
+            for id in &status.related {
+                if annotations[*id].anno != AnnotationType::Spec {
+                    writeln!(w, "  \"anno:{}\" -> \"anno:{}\";", anno_id, id)?;
+                    related.insert(*id);
+                }
+            }
+        }
+
+        let references: BTreeSet<_> = target_report
+            .references
+            .iter()
+            .filter(|r| r.annotation.anno != AnnotationType::Spec)
+            .map(|r| r.annotation_id)
+            .collect();
+
+        for id in references {
+            let annotation = annotations[id];
+            let source = annotation.source.display().to_string();
+
+            writeln!(
+                w,
+                "  \"anno:{}\" [label={:?}];",
+                id,
+                format!("{}\n{}:{}", annotation.anno, source, annotation.anno_line)
+            )?;
+            writeln!(
+                w,
+                "  \"anno:{}\" -> {:?};",
+                id,
+                format!("source:{}", source)
+            )?;
+
+            if !related.contains(&id) {
+                writeln!(w, "  {:?} -> \"anno:{}\" [style=dashed];", spec, id)?;
+            }
+
+            sources.insert(source);
+        }
+    }
+
+    for source in sources {
+        writeln!(
+            w,
+            "  {:?} [label={:?}, shape=note];",
+            format!("source:{}", source),
+            source
+        )?;
+    }
+
+    writeln!(w, "}}")?;
+
+    Ok(())
+}
+
+fn requirement_label(requirement: &Annotation) -> String {
+    let section = requirement.target_section().unwrap_or_default();
+    let mut quote: String = requirement.quote.chars().take(QUOTE_LEN).collect();
+    if quote.len() < requirement.quote.len() {
+        quote.push_str("...");
+    }
+    format!("{}\n{}", section, quote)
+}
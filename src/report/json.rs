@@ -115,6 +115,38 @@ pub fn report_writer<Output: Write>(
         if let Some(link) = report.issue_link {
             kv!(obj, s!("issue_link"), s!(link));
         }
+        if !report.labels.is_empty() {
+            kv!(
+                obj,
+                s!("labels"),
+                obj!(|obj| {
+                    for label in report.labels {
+                        kv!(obj, s!(label.key), s!(label.value));
+                    }
+                })
+            );
+        }
+
+        if !report.languages.is_empty() {
+            kv!(
+                obj,
+                s!("languages"),
+                obj!(|obj| {
+                    for (name, language) in report.languages {
+                        kv!(
+                            obj,
+                            s!(name),
+                            obj!(|obj| {
+                                kv!(obj, s!("files"), w!(language.files));
+                                kv!(obj, s!("annotated_files"), w!(language.annotated));
+                                kv!(obj, s!("lines"), w!(language.lines));
+                                kv!(obj, s!("annotations"), w!(language.annotations));
+                            })
+                        );
+                    }
+                })
+            );
+        }
 
         kv!(
             obj,
@@ -154,6 +186,10 @@ pub fn report_writer<Output: Write>(
                                 kv!(obj, s!("level"), su!(annotation.level));
                             }
 
+                            if !annotation.quote.is_empty() {
+                                kv!(obj, s!("quote"), s!(annotation.quote));
+                            }
+
                             if !annotation.comment.is_empty() {
                                 kv!(obj, s!("comment"), s!(annotation.comment));
                             }
@@ -166,6 +202,10 @@ pub fn report_writer<Output: Write>(
                                 kv!(obj, s!("tracking_issue"), s!(annotation.tracking_issue));
                             }
 
+                            if let Some(expires) = annotation.expires {
+                                kv!(obj, s!("expires"), s!(expires.to_string()));
+                            }
+
                             if !annotation.tags.is_empty() {
                                 kv!(
                                     obj,
@@ -183,6 +223,8 @@ pub fn report_writer<Output: Write>(
             })
         );
 
+        let annotations: Vec<_> = report.annotations.iter().collect();
+
         kv!(
             obj,
             s!("statuses"),
@@ -208,6 +250,16 @@ pub fn report_writer<Output: Write>(
                                 status!(exception);
                                 status!(todo);
 
+                                // user-defined metrics are summed across the requirement and
+                                // everything that references it
+                                let metric: u64 = core::iter::once(anno_id)
+                                    .chain(&status.related)
+                                    .filter_map(|id| annotations[*id].metric)
+                                    .sum();
+                                if metric > 0 {
+                                    kv!(obj, su!("metric"), w!(metric));
+                                }
+
                                 if !status.related.is_empty() {
                                     kv!(
                                         obj,
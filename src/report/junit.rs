@@ -0,0 +1,142 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{status::Spec, ReportResult};
+use crate::annotation::{Annotation, AnnotationType};
+use std::{
+    fs::File,
+    io::{BufWriter, Error, Write},
+    path::Path,
+};
+
+/// Writes a JUnit XML file with a test suite per specification and a test case per requirement
+///
+/// Requirements that are both cited and tested pass, exceptions are skipped and anything else
+/// fails.
+pub fn report(report: &ReportResult, file: &Path) -> Result<(), Error> {
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut file = BufWriter::new(File::create(file)?);
+    report_writer(report, &mut file)?;
+    file.flush()
+}
+
+enum Outcome {
+    Passed,
+    Failed(&'static str),
+    Skipped(String),
+}
+
+impl Outcome {
+    fn new(status: &Spec, annotations: &[&Annotation]) -> Self {
+        if status.exception > 0 {
+            let reasons: Vec<_> = status
+                .related
+                .iter()
+                .map(|id| annotations[*id])
+                .filter(|anno| anno.anno == AnnotationType::Exception)
+                .map(|anno| anno.comment.as_str())
+                .filter(|reason| !reason.is_empty())
+                .collect();
+            return Self::Skipped(reasons.join("; "));
+        }
+
+        if status.implication > 0 {
+            return Self::Passed;
+        }
+
+        match (status.citation > 0, status.test > 0) {
+            (true, true) => Self::Passed,
+            (false, true) => Self::Failed("missing citation"),
+            (true, false) => Self::Failed("missing test"),
+            (false, false) => Self::Failed("missing citation and test"),
+        }
+    }
+}
+
+pub fn report_writer<W: Write>(report: &ReportResult, w: &mut W) -> Result<(), Error> {
+    let annotations: Vec<_> = report.annotations.iter().collect();
+
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(w, r#"<testsuites name="duvet">"#)?;
+
+    for (target, target_report) in &report.targets {
+        let outcomes: Vec<_> = target_report
+            .statuses
+            .iter()
+            .map(|(id, status)| (annotations[*id], Outcome::new(status, &annotations)))
+            .collect();
+
+        let failures = outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+            .count();
+        let skipped = outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, Outcome::Skipped(_)))
+            .count();
+
+        let suite = escape(&target.path.to_string());
+
+        writeln!(
+            w,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}">"#,
+            suite,
+            outcomes.len(),
+            failures,
+            skipped
+        )?;
+
+        for (requirement, outcome) in &outcomes {
+            let name = format!(
+                "{}: {}",
+                requirement.target_section().unwrap_or_default(),
+                requirement.quote
+            );
+
+            write!(
+                w,
+                r#"    <testcase classname="{}" name="{}""#,
+                suite,
+                escape(&name)
+            )?;
+
+            match outcome {
+                Outcome::Passed => writeln!(w, "/>")?,
+                Outcome::Failed(message) => {
+                    writeln!(w, ">")?;
+                    writeln!(w, r#"      <failure message="{}"/>"#, message)?;
+                    writeln!(w, "    </testcase>")?;
+                }
+                Outcome::Skipped(message) => {
+                    writeln!(w, ">")?;
+                    writeln!(w, r#"      <skipped message="{}"/>"#, escape(message))?;
+                    writeln!(w, "    </testcase>")?;
+                }
+            }
+        }
+
+        writeln!(w, "  </testsuite>")?;
+    }
+
+    writeln!(w, "</testsuites>")?;
+
+    Ok(())
+}
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
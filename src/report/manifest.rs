@@ -0,0 +1,97 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{summary::Summary, ReportResult};
+use std::{
+    fs::File,
+    io::{BufWriter, Error, Write},
+    path::Path,
+};
+use v_jsonescape::escape;
+
+/// Incremented any time the structure of the manifest changes
+const MANIFEST_VERSION: u32 = 1;
+
+/// Writes a compliance manifest listing each specification, a digest of the contents that
+/// were checked and the percentage of requirements that were fulfilled
+///
+/// The manifest is intended to be attached to releases so supply-chain tooling can record
+/// which specifications a build was checked against. The digest is the SHA-256 of the
+/// specification file, so it can be checked with standard tools like `sha256sum`.
+pub fn report(report: &ReportResult, file: &Path) -> Result<(), Error> {
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut file = BufWriter::new(File::create(file)?);
+    report_writer(report, &mut file)?;
+    file.flush()
+}
+
+pub fn report_writer<W: Write>(report: &ReportResult, w: &mut W) -> Result<(), Error> {
+    write!(w, "{{")?;
+    write!(w, r#""manifestVersion":{},"#, MANIFEST_VERSION)?;
+    write!(
+        w,
+        r#""tool":{{"name":"duvet","version":"{}"}},"#,
+        env!("CARGO_PKG_VERSION")
+    )?;
+    if !report.labels.is_empty() {
+        write!(w, r#""labels":{{"#)?;
+        for (idx, label) in report.labels.iter().enumerate() {
+            if idx > 0 {
+                write!(w, ",")?;
+            }
+            write!(w, r#""{}":"{}""#, escape(&label.key), escape(&label.value))?;
+        }
+        write!(w, "}},")?;
+    }
+    write!(w, r#""specifications":["#)?;
+
+    for (idx, (target, target_report)) in report.targets.iter().enumerate() {
+        if idx > 0 {
+            write!(w, ",")?;
+        }
+
+        let summary = Summary::from_target(target_report);
+
+        write!(w, "{{")?;
+        write!(w, r#""target":"{}","#, escape(&target.path.to_string()))?;
+        if let Some(title) = &target_report.specification.title {
+            write!(w, r#""title":"{}","#, escape(title))?;
+        }
+        write!(w, r#""format":"{}","#, target_report.specification.format)?;
+        let contents = std::fs::read(target.path.local(report.spec_path))?;
+        write!(w, r#""digest":"sha256:{}","#, sha256(&contents))?;
+        write!(w, r#""requirements":{},"#, summary.requirements)?;
+        write!(
+            w,
+            r#""fulfilled":{},"#,
+            percent(summary.fulfilled_coverage())
+        )?;
+        write!(w, r#""cited":{},"#, percent(summary.citation_coverage()))?;
+        write!(w, r#""tested":{}"#, percent(summary.test_coverage()))?;
+        write!(w, "}}")?;
+    }
+
+    writeln!(w, "]}}")?;
+
+    Ok(())
+}
+
+/// Percentages are `null` for specifications without any requirements
+fn percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| format!("{:.1}", value))
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    use core::fmt::Write;
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::with_capacity(64), |mut out, byte| {
+            let _ = write!(out, "{:02x}", byte);
+            out
+        })
+}
@@ -2,30 +2,44 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    annotation::{Annotation, AnnotationLevel, AnnotationSet, AnnotationSetExt},
+    annotation::{
+        Annotation, AnnotationLevel, AnnotationSet, AnnotationSetExt, AnnotationType, Date,
+    },
+    extract, pattern,
     project::Project,
-    specification::Specification,
+    source::SourceFile,
+    specification::{is_line_anchor, Specification},
     target::Target,
     Error,
 };
-use anyhow::anyhow;
-use core::fmt;
+use anyhow::{anyhow, Context};
+use core::{fmt, str::FromStr};
 use rayon::prelude::*;
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
 };
 use structopt::StructOpt;
 
+mod badge;
+mod baseline;
 mod ci;
+mod evidence;
+mod graph;
 mod html;
 mod json;
+mod junit;
 mod lcov;
+mod manifest;
 mod stats;
 mod status;
+mod summary;
 
 use stats::Statistics;
 
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
 #[derive(Debug, StructOpt)]
 pub struct Report {
     #[structopt(flatten)]
@@ -40,6 +54,27 @@ pub struct Report {
     #[structopt(long)]
     html: Option<PathBuf>,
 
+    /// Directory to write a requirement coverage badge to
+    #[structopt(long)]
+    badge: Option<PathBuf>,
+
+    /// Path to write a JUnit XML file with a test case for each requirement
+    #[structopt(long)]
+    junit: Option<PathBuf>,
+
+    /// Path to write a GraphViz graph of the specifications, requirements and annotations to
+    #[structopt(long)]
+    graph: Option<PathBuf>,
+
+    /// Path to write a compliance manifest to, listing each specification and how much of it
+    /// is fulfilled
+    #[structopt(long)]
+    manifest: Option<PathBuf>,
+
+    /// Directory to write per-requirement evidence records to
+    #[structopt(long)]
+    evidence: Option<PathBuf>,
+
     #[structopt(long)]
     require_citations: Option<Option<bool>>,
 
@@ -49,11 +84,69 @@ pub struct Report {
     #[structopt(long)]
     ci: bool,
 
+    /// Print the percentage of cited, tested and excepted requirements for each specification
+    #[structopt(long)]
+    summary: bool,
+
+    /// Only print the summary; no reports are written
+    #[structopt(long = "summary-only")]
+    summary_only: bool,
+
+    /// Fail if the percentage of cited or excepted requirements is below the given value
+    #[structopt(long = "min-citation-coverage", value_name = "percent")]
+    min_citation_coverage: Option<f64>,
+
+    /// Fail if the percentage of tested or excepted requirements is below the given value
+    #[structopt(long = "min-test-coverage", value_name = "percent")]
+    min_test_coverage: Option<f64>,
+
+    /// Previously exported `--json` report to compare against; fails only on regressions
+    #[structopt(long)]
+    baseline: Option<PathBuf>,
+
+    /// Treat annotations that would otherwise be skipped as errors
+    #[structopt(long)]
+    strict: bool,
+
+    /// Warn about exceptions that expire within the given number of days
+    #[structopt(long = "expiry-warning-days", default_value = "30")]
+    expiry_warning_days: u32,
+
+    /// Only allow exceptions for SHOULD and MAY requirements
+    #[structopt(long = "restrict-exceptions")]
+    restrict_exceptions: bool,
+
     #[structopt(long)]
     blob_link: Option<String>,
 
     #[structopt(long)]
     issue_link: Option<String>,
+
+    /// Attaches a `key=value` label to the JSON report and manifest
+    #[structopt(long = "label", number_of_values = 1)]
+    labels: Vec<Label>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for Label {
+    type Err = Error;
+
+    fn from_str(v: &str) -> Result<Self, Self::Err> {
+        let (key, value) = v
+            .split_once('=')
+            .filter(|(key, _)| !key.is_empty())
+            .ok_or_else(|| anyhow!(format!("invalid label {:?}; expected key=value", v)))?;
+
+        Ok(Self {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord)]
@@ -68,29 +161,115 @@ struct Reference<'a> {
 
 #[derive(Debug)]
 enum ReportError<'a> {
-    QuoteMismatch { annotation: &'a Annotation },
-    MissingSection { annotation: &'a Annotation },
+    QuoteMismatch {
+        annotation: &'a Annotation,
+        section: &'a str,
+        closest: Option<String>,
+    },
+    MissingSection {
+        annotation: &'a Annotation,
+        suggestions: Vec<&'a str>,
+    },
+    MustException {
+        annotation: &'a Annotation,
+        requirement: &'a Annotation,
+    },
+    MissingSectionId {
+        annotation: &'a Annotation,
+    },
+    MissingLineAnchorQuote {
+        annotation: &'a Annotation,
+    },
+    ExpiredException {
+        annotation: &'a Annotation,
+        expires: Date,
+    },
 }
 
 impl<'a> fmt::Display for ReportError<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::QuoteMismatch { annotation } => write!(
+            Self::QuoteMismatch {
+                annotation,
+                section,
+                closest,
+            } => {
+                write!(
+                    f,
+                    "{}#{}:{} - quote not found in {:?}",
+                    annotation.source.display(),
+                    annotation.anno_line,
+                    annotation.anno_column,
+                    annotation.target,
+                )?;
+
+                // show the closest spec text so the spec doesn't need to be opened to fix the quote
+                write!(f, "\n  --> {}", section)?;
+                if let Some(closest) = closest {
+                    for line in closest.lines() {
+                        write!(f, "\n   | {}", line.trim())?;
+                    }
+                }
+
+                Ok(())
+            }
+            Self::MissingSection {
+                annotation,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "{}#{}:{} - section {:?} not found in {:?}",
+                    annotation.source.display(),
+                    annotation.anno_line,
+                    annotation.anno_column,
+                    annotation.target_section().unwrap_or("-"),
+                    annotation.target_path(),
+                )?;
+
+                if !suggestions.is_empty() {
+                    write!(f, "; did you mean {:?}?", suggestions.join(", "))?;
+                }
+
+                Ok(())
+            }
+            Self::MustException {
+                annotation,
+                requirement,
+            } => write!(
                 f,
-                "{}#{}:{} - quote not found in {:?}",
+                "{}#{}:{} - exception is not allowed for MUST requirement {:?}",
+                annotation.source.display(),
+                annotation.anno_line,
+                annotation.anno_column,
+                requirement.quote,
+            ),
+            Self::MissingSectionId { annotation } => write!(
+                f,
+                "{}#{}:{} - {:?} does not reference a section",
                 annotation.source.display(),
                 annotation.anno_line,
                 annotation.anno_column,
                 annotation.target,
             ),
-            Self::MissingSection { annotation } => write!(
+            Self::MissingLineAnchorQuote { annotation } => write!(
                 f,
-                "{}#{}:{} - section {:?} not found in {:?}",
+                "{}#{}:{} - {:?} references lines without a quote so changes to the lines can't be detected",
                 annotation.source.display(),
                 annotation.anno_line,
                 annotation.anno_column,
-                annotation.target_section().unwrap_or("-"),
-                annotation.target_path(),
+                annotation.target,
+            ),
+            Self::ExpiredException {
+                annotation,
+                expires,
+            } => write!(
+                f,
+                "{}#{}:{} - exception expired on {}",
+                annotation.source.display(),
+                annotation.anno_line,
+                annotation.anno_column,
+                expires,
             ),
         }
     }
@@ -98,15 +277,40 @@ impl<'a> fmt::Display for ReportError<'a> {
 
 impl Report {
     pub fn exec(&self) -> Result<(), Error> {
+        // JSON consumers handle duplicate keys inconsistently so they're rejected up front
+        let mut label_keys = BTreeSet::new();
+        for label in &self.labels {
+            if !label_keys.insert(&label.key) {
+                return Err(anyhow!(format!("duplicate label {:?}", label.key)));
+            }
+        }
+
         let project_sources = self.project.sources()?;
 
+        let mut languages = summary::Languages::new();
+
         let annotations: AnnotationSet = project_sources
             .par_iter()
-            .flat_map(|source| {
-                // TODO gracefully handle error
-                source
-                    .annotations()
-                    .unwrap_or_else(|_| panic!("could not extract annotations from {:?}", source))
+            .map(|source| {
+                let (annotations, lines) = source.annotations().with_context(|| {
+                    format!(
+                        "could not extract annotations from {}",
+                        source.path().display()
+                    )
+                })?;
+                Ok((source, annotations, lines))
+            })
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .flat_map(|(source, annotations, lines)| {
+                // spec files are requirements rather than source code
+                if let SourceFile::Text(_, path) = source {
+                    languages
+                        .entry(pattern::language(path))
+                        .or_default()
+                        .record(lines, annotations.len());
+                }
+                annotations
             })
             .collect();
 
@@ -115,20 +319,37 @@ impl Report {
         let contents: HashMap<_, _> = targets
             .par_iter()
             .map(|target| {
-                let contents = target.path.load(self.project.spec_path.as_deref()).unwrap();
-                (target, contents)
+                let contents = target
+                    .path
+                    .load(self.project.spec_path.as_deref(), self.project.fetch)
+                    .with_context(|| format!("could not load specification {}", target.path))?;
+                Ok((target, contents))
             })
-            .collect();
+            .collect::<Result<_, Error>>()?;
+
+        let reference_map = annotations.reference_map()?;
 
         let specifications: HashMap<_, _> = contents
             .par_iter()
             .map(|(target, contents)| {
-                let spec = target.format.parse(contents).unwrap();
-                (target, spec)
-            })
-            .collect();
+                let mut spec = target
+                    .format
+                    .parse(contents)
+                    .with_context(|| format!("could not parse specification {}", target.path))?;
+
+                // line anchors don't exist in the document so they need to be
+                // created for each reference
+                for (reference_target, section_id) in reference_map.keys() {
+                    if let Some(section_id) = section_id {
+                        if reference_target == *target {
+                            spec.insert_line_anchor(section_id, contents);
+                        }
+                    }
+                }
 
-        let reference_map = annotations.reference_map()?;
+                Ok((target, spec))
+            })
+            .collect::<Result<_, Error>>()?;
 
         let results: Vec<_> = reference_map
             .par_iter()
@@ -142,6 +363,15 @@ impl Report {
                         let contents = section.contents();
 
                         for (annotation_id, annotation) in annotations {
+                            // the quote is the only thing tying a line anchor to its contents
+                            if annotation.quote.is_empty() && is_line_anchor(section_id) {
+                                results.push(Err((
+                                    target,
+                                    ReportError::MissingLineAnchorQuote { annotation },
+                                )));
+                                continue;
+                            }
+
                             if annotation.quote.is_empty() {
                                 // empty quotes don't count towards coverage but are still
                                 // references
@@ -176,18 +406,40 @@ impl Report {
                                     )));
                                 }
                             } else {
-                                results
-                                    .push(Err((target, ReportError::QuoteMismatch { annotation })));
+                                let closest = crate::text::closest(&annotation.quote, &contents)
+                                    .map(|range| contents[range].to_string());
+                                results.push(Err((
+                                    target,
+                                    ReportError::QuoteMismatch {
+                                        annotation,
+                                        section: &section.full_title,
+                                        closest,
+                                    },
+                                )));
                             }
                         }
                     } else {
+                        let suggestions = spec.similar_sections(section_id);
                         for (_, annotation) in annotations {
-                            results.push(Err((target, ReportError::MissingSection { annotation })));
+                            results.push(Err((
+                                target,
+                                ReportError::MissingSection {
+                                    annotation,
+                                    suggestions: suggestions.clone(),
+                                },
+                            )));
                         }
                     }
                 } else {
-                    // TODO
-                    eprintln!("TOTAL REFERENCE {:?}", annotations);
+                    // annotations without a section are only diagnosed under `--strict`
+                    if self.strict {
+                        for (_, annotation) in annotations {
+                            results
+                                .push(Err((target, ReportError::MissingSectionId { annotation })));
+                        }
+                    } else if !self.project.quiet {
+                        eprintln!("TOTAL REFERENCE {:?}", annotations);
+                    }
                 }
 
                 // TODO upgrade levels whenever they overlap
@@ -201,6 +453,9 @@ impl Report {
             annotations: &annotations,
             blob_link: self.blob_link.as_deref(),
             issue_link: self.issue_link.as_deref(),
+            labels: &self.labels,
+            languages: &languages,
+            spec_path: self.project.spec_path.as_deref(),
         };
         let mut errors = BTreeSet::new();
 
@@ -247,20 +502,149 @@ impl Report {
             .par_iter_mut()
             .for_each(|(_, target)| target.statuses.populate(&target.references));
 
+        self.check_expirations(&annotations)?;
+
+        if self.restrict_exceptions {
+            let annotations: Vec<_> = annotations.iter().collect();
+
+            for target in report.targets.values() {
+                for (anno_id, status) in target.statuses.iter() {
+                    let requirement = annotations[*anno_id];
+
+                    // requirements without an explicit level use the key words in the quote
+                    let level = match requirement.level {
+                        AnnotationLevel::Auto => {
+                            extract::quote_level(&requirement.quote).unwrap_or_default()
+                        }
+                        level => level,
+                    };
+
+                    if level != AnnotationLevel::Must {
+                        continue;
+                    }
+
+                    for id in &status.related {
+                        let annotation = annotations[*id];
+                        if annotation.anno == AnnotationType::Exception {
+                            let err = ReportError::MustException {
+                                annotation,
+                                requirement,
+                            };
+                            errors.insert(err.to_string());
+                        }
+                    }
+                }
+            }
+
+            if !errors.is_empty() {
+                for error in &errors {
+                    eprintln!("{}", error);
+                }
+
+                return Err(anyhow!(
+                    "exceptions were found for MUST requirements. no reports were generated"
+                ));
+            }
+        }
+
+        // compare against the baseline before any of the reports are written, since the baseline
+        // may be the same file as the `--json` output
+        if let Some(file) = &self.baseline {
+            baseline::report(&report, file, self.project.quiet)?;
+        }
+
+        if !self.summary_only {
+            self.write_reports(&report)?;
+        }
+
+        if self.ci {
+            ci::report(&report)?;
+        }
+
+        let thresholds = summary::Thresholds {
+            citations: self.min_citation_coverage,
+            tests: self.min_test_coverage,
+        };
+        let print = (self.summary || self.summary_only) && !self.project.quiet;
+        summary::report(&report, print, thresholds)?;
+
+        Ok(())
+    }
+
+    fn check_expirations(&self, annotations: &AnnotationSet) -> Result<(), Error> {
+        let today = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() / SECONDS_PER_DAY;
+        let today = today as i64;
+        let mut errors = BTreeSet::new();
+
+        for annotation in annotations {
+            let expires = match annotation.expires {
+                Some(expires) if annotation.anno == AnnotationType::Exception => expires,
+                _ => continue,
+            };
+
+            let remaining = expires.days() - today;
+
+            if remaining < 0 {
+                let err = ReportError::ExpiredException {
+                    annotation,
+                    expires,
+                };
+                errors.insert(err.to_string());
+            } else if remaining <= self.expiry_warning_days as i64 && !self.project.quiet {
+                eprintln!(
+                    "{}#{}:{} - exception expires on {}",
+                    annotation.source.display(),
+                    annotation.anno_line,
+                    annotation.anno_column,
+                    expires,
+                );
+            }
+        }
+
+        if !errors.is_empty() {
+            for error in &errors {
+                eprintln!("{}", error);
+            }
+
+            return Err(anyhow!(
+                "expired exceptions were found. no reports were generated"
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn write_reports(&self, report: &ReportResult) -> Result<(), Error> {
         if let Some(dir) = &self.lcov {
-            lcov::report(&report, dir)?;
+            lcov::report(report, dir)?;
         }
 
         if let Some(file) = &self.json {
-            json::report(&report, file)?;
+            json::report(report, file)?;
         }
 
         if let Some(dir) = &self.html {
-            html::report(&report, dir)?;
+            html::report(report, dir)?;
         }
 
-        if self.ci {
-            ci::report(&report)?;
+        if let Some(file) = &self.junit {
+            junit::report(report, file)?;
+        }
+
+        if let Some(dir) = &self.badge {
+            badge::report(report, dir)?;
+        }
+
+        if let Some(file) = &self.graph {
+            graph::report(report, file)?;
+        }
+
+        if let Some(file) = &self.manifest {
+            manifest::report(report, file)?;
+        }
+
+        if let Some(dir) = &self.evidence {
+            evidence::report(report, dir)?;
         }
 
         Ok(())
@@ -289,6 +673,9 @@ pub struct ReportResult<'a> {
     pub annotations: &'a AnnotationSet,
     pub blob_link: Option<&'a str>,
     pub issue_link: Option<&'a str>,
+    pub labels: &'a [Label],
+    pub languages: &'a summary::Languages,
+    pub spec_path: Option<&'a str>,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,204 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{status::Spec, ReportResult, TargetReport};
+use crate::Error;
+use anyhow::anyhow;
+use core::fmt;
+use std::collections::BTreeMap;
+
+/// Requirement coverage thresholds, in percent
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Thresholds {
+    pub citations: Option<f64>,
+    pub tests: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Summary {
+    pub requirements: usize,
+    pub cited: usize,
+    pub tested: usize,
+    pub excepted: usize,
+    pub todo: usize,
+    pub complete: usize,
+    citation_covered: usize,
+    test_covered: usize,
+}
+
+impl Summary {
+    pub fn from_target(report: &TargetReport) -> Self {
+        let mut summary = Self::default();
+        for status in report.statuses.values() {
+            summary.record(status);
+        }
+        summary
+    }
+
+    pub fn from_report(report: &ReportResult) -> Self {
+        let mut summary = Self::default();
+        for target_report in report.targets.values() {
+            summary.merge(&Self::from_target(target_report));
+        }
+        summary
+    }
+
+    fn record(&mut self, status: &Spec) {
+        self.requirements += 1;
+
+        if status.incomplete == 0 {
+            self.complete += 1;
+        }
+
+        // implications cover both the citation and test
+        if status.citation > 0 || status.implication > 0 {
+            self.cited += 1;
+        }
+        if status.test > 0 || status.implication > 0 {
+            self.tested += 1;
+        }
+        if status.exception > 0 {
+            self.excepted += 1;
+        }
+        if status.todo > 0 {
+            self.todo += 1;
+        }
+
+        // exceptions count towards both thresholds, same as the `--ci` checks
+        let excepted = status.exception > 0;
+        if excepted || status.citation > 0 || status.implication > 0 {
+            self.citation_covered += 1;
+        }
+        if excepted || status.test > 0 || status.implication > 0 {
+            self.test_covered += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.requirements += other.requirements;
+        self.cited += other.cited;
+        self.tested += other.tested;
+        self.excepted += other.excepted;
+        self.todo += other.todo;
+        self.complete += other.complete;
+        self.citation_covered += other.citation_covered;
+        self.test_covered += other.test_covered;
+    }
+
+    /// Percentage of requirements that are fully covered
+    pub fn fulfilled_coverage(&self) -> Option<f64> {
+        self.percent(self.complete)
+    }
+
+    /// Percentage of requirements that are cited or excepted
+    pub fn citation_coverage(&self) -> Option<f64> {
+        self.percent(self.citation_covered)
+    }
+
+    /// Percentage of requirements that are tested or excepted
+    pub fn test_coverage(&self) -> Option<f64> {
+        self.percent(self.test_covered)
+    }
+
+    /// Returns `None` when there aren't any requirements to cover
+    fn percent(&self, count: usize) -> Option<f64> {
+        if self.requirements == 0 {
+            return None;
+        }
+        Some(count as f64 * 100.0 / self.requirements as f64)
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let percent = |count| self.percent(count).unwrap_or_default();
+
+        if self.requirements == 0 {
+            return write!(f, "0 requirements");
+        }
+
+        write!(
+            f,
+            "{} requirements, {:.1}% cited, {:.1}% tested, {:.1}% excepted, {:.1}% todo",
+            self.requirements,
+            percent(self.cited),
+            percent(self.tested),
+            percent(self.excepted),
+            percent(self.todo),
+        )
+    }
+}
+
+/// Source statistics keyed by language name
+pub type Languages = BTreeMap<&'static str, Language>;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Language {
+    pub files: usize,
+    /// Files that contain at least one annotation
+    pub annotated: usize,
+    pub lines: usize,
+    pub annotations: usize,
+}
+
+impl Language {
+    pub fn record(&mut self, lines: usize, annotations: usize) {
+        self.files += 1;
+        self.lines += lines;
+        self.annotations += annotations;
+        if annotations > 0 {
+            self.annotated += 1;
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} files ({} annotated), {} lines, {} annotations",
+            self.files, self.annotated, self.lines, self.annotations
+        )
+    }
+}
+
+pub fn report(report: &ReportResult, print: bool, thresholds: Thresholds) -> Result<(), Error> {
+    let mut total = Summary::default();
+
+    for (target, target_report) in &report.targets {
+        let summary = Summary::from_target(target_report);
+        if print {
+            println!("{}: {}", target.path, summary);
+        }
+        total.merge(&summary);
+    }
+
+    if print {
+        println!("total: {}", total);
+
+        for (name, language) in report.languages {
+            println!("{}: {}", name, language);
+        }
+    }
+
+    // specifications without any requirements can't fall below a threshold
+    if let (Some(min), Some(actual)) = (thresholds.citations, total.citation_coverage()) {
+        if actual < min {
+            return Err(anyhow!(format!(
+                "citation coverage {:.1}% is below the required {:.1}%",
+                actual, min
+            )));
+        }
+    }
+
+    if let (Some(min), Some(actual)) = (thresholds.tests, total.test_coverage()) {
+        if actual < min {
+            return Err(anyhow!(format!(
+                "test coverage {:.1}% is below the required {:.1}%",
+                actual, min
+            )));
+        }
+    }
+
+    Ok(())
+}
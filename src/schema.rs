@@ -0,0 +1,31 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Error;
+use std::{io::Write, path::PathBuf};
+use structopt::StructOpt;
+
+/// JSON Schema describing the output of `report --json`
+pub const REPORT: &str = include_str!("./report/schema.json");
+
+#[derive(Debug, StructOpt)]
+pub struct Schema {
+    /// Writes the schema to the given path instead of stdout
+    #[structopt(short, long)]
+    out: Option<PathBuf>,
+}
+
+impl Schema {
+    pub fn exec(&self) -> Result<(), Error> {
+        if let Some(out) = &self.out {
+            if let Some(parent) = out.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(out, REPORT)?;
+        } else {
+            std::io::stdout().write_all(REPORT.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
@@ -7,9 +7,12 @@ use crate::{
     specification::Format,
     Error,
 };
-use anyhow::{anyhow, Context};
+use anyhow::anyhow;
 use serde::Deserialize;
-use std::{collections::BTreeSet, path::PathBuf};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
 pub enum SourceFile<'a> {
@@ -18,15 +21,21 @@ pub enum SourceFile<'a> {
 }
 
 impl<'a> SourceFile<'a> {
-    pub fn annotations(&self) -> Result<AnnotationSet, Error> {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Text(_, file) => file,
+            Self::Spec(file) => file,
+        }
+    }
+
+    /// Extracts the annotations in the file, along with the number of lines it contains
+    pub fn annotations(&self) -> Result<(AnnotationSet, usize), Error> {
         let mut annotations = AnnotationSet::new();
         match self {
             Self::Text(pattern, file) => {
                 let text = std::fs::read_to_string(file)?;
-                pattern
-                    .extract(&text, file, &mut annotations)
-                    .with_context(|| file.display().to_string())?;
-                Ok(annotations)
+                pattern.extract(&text, file, &mut annotations)?;
+                Ok((annotations, text.lines().count()))
             }
             Self::Spec(file) => {
                 let text = std::fs::read_to_string(file)?;
@@ -40,7 +49,10 @@ impl<'a> SourceFile<'a> {
                 for anno in specs.todos {
                     annotations.insert(anno.into_annotation(file.clone(), &specs.target)?);
                 }
-                Ok(annotations)
+                for anno in specs.audits {
+                    annotations.insert(anno.into_annotation(file.clone(), &specs.target)?);
+                }
+                Ok((annotations, text.lines().count()))
             }
         }
     }
@@ -62,6 +74,10 @@ struct Specs<'a> {
     #[serde(borrow)]
     #[serde(alias = "TODO", alias = "todo", default)]
     todos: Vec<Todo<'a>>,
+
+    #[serde(borrow)]
+    #[serde(alias = "audit", default)]
+    audits: Vec<Audit<'a>>,
 }
 
 #[derive(Deserialize)]
@@ -95,6 +111,8 @@ impl<'a> Spec<'a> {
             manifest_dir: source.clone(),
             feature: Default::default(),
             tags: Default::default(),
+            expires: None,
+            metric: None,
             tracking_issue: Default::default(),
             source,
             level: if let Some(level) = self.level {
@@ -117,6 +135,7 @@ struct Exception<'a> {
     target: Option<String>,
     quote: &'a str,
     reason: String,
+    expires: Option<&'a str>,
 }
 
 impl<'a> Exception<'a> {
@@ -141,6 +160,8 @@ impl<'a> Exception<'a> {
             manifest_dir: source.clone(),
             feature: Default::default(),
             tags: Default::default(),
+            expires: self.expires.map(str::parse).transpose()?,
+            metric: None,
             tracking_issue: Default::default(),
             source,
             level: AnnotationLevel::Auto,
@@ -184,6 +205,8 @@ impl<'a> Todo<'a> {
             manifest_dir: source.clone(),
             source,
             tags: self.tags,
+            expires: None,
+            metric: None,
             feature: self.feature.unwrap_or_default(),
             tracking_issue: self.tracking_issue.unwrap_or_default(),
             level: AnnotationLevel::Auto,
@@ -192,6 +215,84 @@ impl<'a> Todo<'a> {
     }
 }
 
+/// A decision recorded by an auditor for a requirement
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum AuditDecision {
+    AcceptedRisk,
+    NotApplicable,
+    VerifiedManually,
+}
+
+impl AuditDecision {
+    fn annotation_type(self) -> AnnotationType {
+        match self {
+            // the requirement is intentionally not fulfilled
+            Self::AcceptedRisk | Self::NotApplicable => AnnotationType::Exception,
+            // the requirement was checked outside of the code base
+            Self::VerifiedManually => AnnotationType::Implication,
+        }
+    }
+
+    fn tag(self) -> &'static str {
+        match self {
+            Self::AcceptedRisk => "audit:accepted-risk",
+            Self::NotApplicable => "audit:not-applicable",
+            Self::VerifiedManually => "audit:verified-manually",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Audit<'a> {
+    target: Option<String>,
+    quote: &'a str,
+    decision: AuditDecision,
+    reason: String,
+    auditor: Option<String>,
+    #[serde(default)]
+    tags: BTreeSet<String>,
+}
+
+impl<'a> Audit<'a> {
+    fn into_annotation(
+        self,
+        source: PathBuf,
+        default_target: &Option<String>,
+    ) -> Result<Annotation, Error> {
+        let mut tags = self.tags;
+        tags.insert(self.decision.tag().to_string());
+        if let Some(auditor) = self.auditor {
+            tags.insert(format!("auditor:{}", auditor));
+        }
+
+        Ok(Annotation {
+            anno_line: 0,
+            anno_column: 0,
+            item_line: 0,
+            item_column: 0,
+            path: String::new(),
+            anno: self.decision.annotation_type(),
+            target: self
+                .target
+                .or_else(|| default_target.as_ref().cloned())
+                .ok_or_else(|| anyhow!("missing target"))?,
+            quote: normalize_quote(self.quote),
+            comment: self.reason,
+            manifest_dir: source.clone(),
+            feature: Default::default(),
+            tracking_issue: Default::default(),
+            tags,
+            expires: None,
+            metric: None,
+            source,
+            level: AnnotationLevel::Auto,
+            format: Format::Auto,
+        })
+    }
+}
+
 fn normalize_quote(s: &str) -> String {
     s.lines().fold(String::new(), |mut s, l| {
         let l = l.trim();
@@ -229,4 +330,40 @@ mod tests {
         ";
         assert_eq!(normalize_quote(sample), "A: * B * C D",);
     }
+
+    #[test]
+    fn test_audit_decisions() {
+        let sample = r#"
+            target = "my-spec.md#section"
+
+            [[audit]]
+            quote = "This MUST be checked"
+            decision = "verified-manually"
+            reason = "Checked during the design review"
+            auditor = "alice"
+
+            [[audit]]
+            quote = "This SHOULD be ignored"
+            decision = "not-applicable"
+            reason = "The feature is disabled"
+        "#;
+
+        let specs = toml::from_str::<Specs>(sample).unwrap();
+        let annotations = specs
+            .audits
+            .into_iter()
+            .map(|audit| {
+                audit
+                    .into_annotation("audit.toml".into(), &specs.target)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(annotations[0].anno, AnnotationType::Implication);
+        assert!(annotations[0].tags.contains("audit:verified-manually"));
+        assert!(annotations[0].tags.contains("auditor:alice"));
+        assert_eq!(annotations[1].anno, AnnotationType::Exception);
+        assert!(annotations[1].tags.contains("audit:not-applicable"));
+        assert_eq!(annotations[1].comment, "The feature is disabled");
+    }
 }
@@ -1,7 +1,10 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{sourcemap::Str, Error};
+use crate::{
+    sourcemap::{LinesIter, Str},
+    Error,
+};
 use anyhow::anyhow;
 use core::{
     cmp::Ordering,
@@ -13,6 +16,7 @@ use std::collections::HashMap;
 
 pub mod ietf;
 pub mod markdown;
+pub mod protobuf;
 
 #[derive(Default)]
 pub struct Specification<'a> {
@@ -62,6 +66,94 @@ impl<'a> Specification<'a> {
             None
         })
     }
+
+    /// Returns the section ids closest to `id`, ordered by similarity
+    pub fn similar_sections(&self, id: &str) -> Vec<&str> {
+        // allow roughly a third of the id to be mistyped
+        let max_distance = (id.len() as u32 / 3).max(2);
+
+        let mut candidates: Vec<_> = self
+            .sections
+            .keys()
+            .map(|candidate| {
+                let distance = triple_accel::levenshtein(id.as_bytes(), candidate.as_bytes());
+                (distance, candidate.as_str())
+            })
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+
+        candidates.sort_unstable();
+
+        candidates
+            .into_iter()
+            .take(3)
+            .map(|(_, candidate)| candidate)
+            .collect()
+    }
+
+    /// Inserts a section covering the lines referenced by an `L<start>-L<end>` anchor
+    ///
+    /// This allows citing documents without any section structure. Returns `false`
+    /// if `id` is not a line anchor or the lines are not in the document.
+    pub fn insert_line_anchor(&mut self, id: &str, contents: &'a str) -> bool {
+        if self.sections.contains_key(id) {
+            return true;
+        }
+
+        let (start, end) = if let Some(range) = parse_line_anchor(id) {
+            range
+        } else {
+            return false;
+        };
+
+        let lines: Vec<_> = LinesIter::new(contents)
+            .skip(start - 1)
+            .take(end - start + 1)
+            .collect();
+
+        if lines.len() != end - start + 1 {
+            return false;
+        }
+
+        let title = if start == end {
+            format!("Line {}", start)
+        } else {
+            format!("Lines {}-{}", start, end)
+        };
+
+        let section = Section {
+            id: id.to_string(),
+            title,
+            full_title: lines[0],
+            lines: lines.into_iter().map(Line::from).collect(),
+        };
+
+        self.sections.insert(section.id.clone(), section);
+
+        true
+    }
+}
+
+/// Returns `true` if the section id is an `L<start>-L<end>` line anchor
+pub fn is_line_anchor(id: &str) -> bool {
+    parse_line_anchor(id).is_some()
+}
+
+/// Parses `L<line>` or `L<start>-L<end>` into an inclusive, 1-based line range
+fn parse_line_anchor(id: &str) -> Option<(usize, usize)> {
+    let (start, end) = match id.split_once('-') {
+        Some((start, end)) => (start, end),
+        None => (id, id),
+    };
+
+    let start: usize = start.strip_prefix('L')?.parse().ok()?;
+    let end: usize = end.strip_prefix('L')?.parse().ok()?;
+
+    if start == 0 || end < start {
+        return None;
+    }
+
+    Some((start, end))
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
@@ -69,6 +161,7 @@ pub enum Format {
     Auto,
     Ietf,
     Markdown,
+    Protobuf,
 }
 
 impl Default for Format {
@@ -83,6 +176,7 @@ impl fmt::Display for Format {
             Self::Auto => "auto",
             Self::Ietf => "ietf",
             Self::Markdown => "markdown",
+            Self::Protobuf => "protobuf",
         };
         write!(f, "{}", v)
     }
@@ -104,6 +198,7 @@ impl Format {
             }
             Self::Ietf => ietf::parse(contents),
             Self::Markdown => markdown::parse(contents),
+            Self::Protobuf => protobuf::parse(contents),
         }?;
 
         if cfg!(debug_assertions) {
@@ -138,6 +233,7 @@ impl FromStr for Format {
             "AUTO" | "auto" => Ok(Self::Auto),
             "IETF" | "ietf" => Ok(Self::Ietf),
             "MARKDOWN" | "markdown" | "md" => Ok(Self::Markdown),
+            "PROTOBUF" | "protobuf" | "proto" => Ok(Self::Protobuf),
             _ => Err(anyhow!(format!("Invalid spec type {:?}", v))),
         }
     }
@@ -308,3 +404,27 @@ impl<'a> Iterator for StrRangeIter<'a> {
         Some((line, range))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn similar_sections_test() {
+        let contents = "# Spec\n\n## Handshake\n\n## Handshake Done\n\n## Transport\n";
+        let spec = Format::Markdown.parse(contents).unwrap();
+
+        assert_eq!(spec.similar_sections("handshak"), vec!["handshake"]);
+        assert_eq!(spec.similar_sections("transprot"), vec!["transport"]);
+        assert!(spec.similar_sections("congestion").is_empty());
+    }
+
+    #[test]
+    fn line_anchor_test() {
+        assert_eq!(parse_line_anchor("L3"), Some((3, 3)));
+        assert_eq!(parse_line_anchor("L3-L10"), Some((3, 10)));
+        assert_eq!(parse_line_anchor("L0"), None);
+        assert_eq!(parse_line_anchor("L10-L3"), None);
+        assert_eq!(parse_line_anchor("section-3"), None);
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Line, Section, Specification};
+use crate::{sourcemap::LinesIter, Error};
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref DECLARATION_RE: Regex =
+        Regex::new(r"^\s*(message|service|rpc|enum)\s+([A-Za-z_][A-Za-z0-9_]*)").unwrap();
+}
+
+/// Parses a Protobuf IDL file into a specification
+///
+/// Each `message`, `enum`, `service` and `rpc` declaration becomes a section, identified
+/// by its name, containing the comment block directly above it. Nested declarations are
+/// qualified with the names of the declarations they're in, e.g. `User.Status`.
+pub fn parse(contents: &str) -> Result<Specification, Error> {
+    let mut spec = Specification::default();
+    let mut comments: Vec<Line> = vec![];
+    // the names of the enclosing declarations along with the brace depth they were opened at
+    let mut scopes: Vec<(String, usize)> = vec![];
+    let mut depth = 0;
+    // declarations open a scope at their next brace, unless they end first
+    let mut pending = None;
+
+    for line in LinesIter::new(contents) {
+        let trimmed = line.trim();
+
+        if let Some(comment) = trimmed.value.strip_prefix("//") {
+            let comment = trimmed.substr(comment).expect("comment is a substring");
+            comments.push(comment.trim().into());
+            continue;
+        }
+
+        if let Some(info) = DECLARATION_RE.captures(&line) {
+            let kind = &info[1];
+            let name = &info[2];
+
+            let id = scopes
+                .iter()
+                .map(|(scope, _)| scope.as_str())
+                .chain(core::iter::once(name))
+                .collect::<Vec<_>>()
+                .join(".");
+
+            if spec.sections.contains_key(&id) {
+                return Err(anyhow!(format!(
+                    "duplicate declaration {:?} on line {}",
+                    id, line.line
+                )));
+            }
+
+            let section = Section {
+                id: id.clone(),
+                title: format!("{} {}", kind, name),
+                full_title: trimmed,
+                lines: core::mem::take(&mut comments),
+            };
+
+            spec.sections.insert(id, section);
+            pending = Some(name.to_string());
+        } else {
+            // comments that are detached from a declaration don't belong to any section
+            comments.clear();
+        }
+
+        let code = line.split("//").next().unwrap_or_default();
+        for c in code.chars() {
+            match c {
+                '{' => {
+                    depth += 1;
+                    if let Some(name) = pending.take() {
+                        scopes.push((name, depth));
+                    }
+                }
+                '}' => {
+                    if matches!(scopes.last(), Some((_, open)) if *open == depth) {
+                        scopes.pop();
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+                ';' => pending = None,
+                _ => {}
+            }
+        }
+    }
+
+    spec.format = super::Format::Protobuf;
+
+    Ok(spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declarations() {
+        let spec = parse(
+            r#"
+syntax = "proto3";
+
+// A detached comment
+
+// Looks up users by id.
+// The server MUST return NOT_FOUND for unknown ids.
+service Users {
+  // Returns a single user.
+  rpc GetUser(GetUserRequest) returns (User);
+}
+
+message User {
+  string id = 1;
+}
+"#,
+        )
+        .unwrap();
+
+        let users = spec.section("Users").unwrap();
+        assert_eq!(users.title, "service Users");
+        assert_eq!(
+            &*users.contents(),
+            "Looks up users by id. The server MUST return NOT_FOUND for unknown ids. "
+        );
+
+        let get_user = spec.section("Users.GetUser").unwrap();
+        assert_eq!(get_user.title, "rpc GetUser");
+        assert_eq!(&*get_user.contents(), "Returns a single user. ");
+
+        let user = spec.section("User").unwrap();
+        assert!(user.lines.is_empty());
+    }
+
+    #[test]
+    fn nested_declarations() {
+        let spec = parse(
+            r#"
+message A {
+  // A's status MUST be set.
+  enum Status {
+    OK = 0;
+  }
+}
+
+message B
+{
+  // B's status MAY be empty.
+  enum Status { OK = 0; }
+  string id = 1;
+}
+
+// Top level status.
+enum Status {
+  OK = 0;
+}
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            &*spec.section("A.Status").unwrap().contents(),
+            "A's status MUST be set. "
+        );
+        assert_eq!(
+            &*spec.section("B.Status").unwrap().contents(),
+            "B's status MAY be empty. "
+        );
+        assert_eq!(
+            &*spec.section("Status").unwrap().contents(),
+            "Top level status. "
+        );
+
+        let err = parse(
+            "message A {}
+message A {}
+",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("duplicate declaration"));
+    }
+}
@@ -2,15 +2,39 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{annotation::Annotation, specification::Format, Error};
-use core::{fmt, str::FromStr};
+use anyhow::anyhow;
+use core::{
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use std::{
     collections::HashSet,
     path::{Path, PathBuf},
 };
+use structopt::StructOpt;
 use url::Url;
 
 pub type TargetSet = HashSet<Target>;
 
+// Controls how remote specifications are fetched into the local `specs` cache.
+//
+// This is a plain comment since structopt would otherwise use it as the `about`
+// text of every command that flattens it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Eq, Ord, Hash, StructOpt)]
+pub struct Fetch {
+    /// Never download specifications and fail if one isn't already cached
+    #[structopt(long)]
+    pub offline: bool,
+
+    /// Revalidate cached specifications with the server, downloading any changes
+    ///
+    /// The ETag of each download is stored in `target/duvet/etags` so the
+    /// `specs` folder only contains the specifications themselves.
+    #[structopt(long = "refresh-specs")]
+    pub refresh_specs: bool,
+}
+
 #[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
 pub struct Target {
     pub path: TargetPath,
@@ -20,10 +44,14 @@ pub struct Target {
 impl Target {
     pub fn from_annotation(anno: &Annotation) -> Result<Self, Error> {
         let path = TargetPath::from_annotation(anno)?;
-        Ok(Self {
-            path,
-            format: anno.format,
-        })
+
+        // protobuf files can't be told apart from IETF text by their contents
+        let format = match anno.format {
+            Format::Auto if anno.target_path().ends_with(".proto") => Format::Protobuf,
+            format => format,
+        };
+
+        Ok(Self { path, format })
     }
 }
 
@@ -72,24 +100,25 @@ impl TargetPath {
         Ok(Self::Path(path))
     }
 
-    pub fn load(&self, spec_download_path: Option<&str>) -> Result<String, Error> {
+    pub fn load(&self, spec_download_path: Option<&str>, fetch: Fetch) -> Result<String, Error> {
         let mut contents = match self {
             Self::Url(url) => {
                 let path = self.local(spec_download_path);
-                if !path.exists() {
-                    std::fs::create_dir_all(path.parent().unwrap())?;
-
-                    let canonical_url = Self::canonical_url(url.as_str());
-
-                    reqwest::blocking::Client::builder()
-                        .build()?
-                        .get(canonical_url)
-                        .header("user-agent", "https://crates.io/crates/cargo-compliance")
-                        .header("accept", "text/plain")
-                        .send()?
-                        .error_for_status()?
-                        .copy_to(&mut std::fs::File::create(&path)?)?;
+                let is_cached = path.exists();
+
+                if fetch.offline {
+                    if !is_cached {
+                        return Err(anyhow!(
+                            "{} is not cached at {} and --offline was set",
+                            url,
+                            path.display()
+                        ));
+                    }
+                } else if !is_cached || fetch.refresh_specs {
+                    let etag_path = Self::etag_path(url, spec_download_path);
+                    Self::download(url, &path, &etag_path)?;
                 }
+
                 std::fs::read_to_string(path)?
             }
             Self::Path(path) => std::fs::read_to_string(path)?,
@@ -103,24 +132,95 @@ impl TargetPath {
         Ok(contents)
     }
 
+    fn download(url: &Url, path: &Path, etag_path: &Path) -> Result<(), Error> {
+        std::fs::create_dir_all(path.parent().unwrap())?;
+
+        let canonical_url = Self::canonical_url(url.as_str());
+
+        let mut request = reqwest::blocking::Client::builder()
+            .build()?
+            .get(canonical_url)
+            .header("user-agent", "https://crates.io/crates/cargo-compliance")
+            .header("accept", "text/plain");
+
+        // revalidate the cached copy if we have one
+        if path.exists() {
+            if let Ok(etag) = std::fs::read_to_string(etag_path) {
+                request = request.header("if-none-match", etag.trim());
+            }
+        }
+
+        let mut response = request.send()?.error_for_status()?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(());
+        }
+
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|etag| etag.to_str().ok())
+            .map(String::from);
+
+        // write to a temporary file first so an interrupted download isn't cached. The file
+        // name is unique to the process and call so concurrent downloads of the same spec,
+        // e.g. when it's cited with multiple formats, don't write to the same file.
+        static DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+        let download_path = path.with_extension(format!(
+            "{}.{}.download",
+            std::process::id(),
+            DOWNLOADS.fetch_add(1, Ordering::Relaxed)
+        ));
+        response.copy_to(&mut std::fs::File::create(&download_path)?)?;
+        std::fs::rename(&download_path, path)?;
+
+        if let Some(etag) = etag {
+            std::fs::create_dir_all(etag_path.parent().unwrap())?;
+            std::fs::write(etag_path, etag)?;
+        } else if etag_path.exists() {
+            std::fs::remove_file(etag_path)?;
+        }
+
+        Ok(())
+    }
+
     pub fn local(&self, spec_download_path: Option<&str>) -> PathBuf {
         match self {
-            Self::Url(url) => {
-                let mut path = if let Some(path_to_spec) = spec_download_path {
-                    PathBuf::from_str(path_to_spec).unwrap()
-                } else {
-                    std::env::current_dir().unwrap()
-                };
-                path.push("specs");
-                path.push(url.host_str().expect("url should have host"));
-                path.extend(url.path_segments().expect("url should have path"));
-                path.set_extension("txt");
-                path
-            }
+            Self::Url(url) => Self::cache_path(url, spec_download_path, &["specs"], "txt"),
             Self::Path(path) => path.clone(),
         }
     }
 
+    /// Path of the ETag recorded for a downloaded specification
+    ///
+    /// These are kept out of the `specs` folder since it is usually committed.
+    fn etag_path(url: &Url, spec_download_path: Option<&str>) -> PathBuf {
+        Self::cache_path(
+            url,
+            spec_download_path,
+            &["target", "duvet", "etags"],
+            "etag",
+        )
+    }
+
+    fn cache_path(
+        url: &Url,
+        spec_download_path: Option<&str>,
+        dir: &[&str],
+        extension: &str,
+    ) -> PathBuf {
+        let mut path = if let Some(path_to_spec) = spec_download_path {
+            PathBuf::from_str(path_to_spec).unwrap()
+        } else {
+            std::env::current_dir().unwrap()
+        };
+        path.extend(dir);
+        path.push(url.host_str().expect("url should have host"));
+        path.extend(url.path_segments().expect("url should have path"));
+        path.set_extension(extension);
+        path
+    }
+
     fn canonical_url(url: &str) -> String {
         // rewrite some of the IETF links for convenience
         if let Some(rfc) = url.strip_prefix("https://tools.ietf.org/rfc/") {
@@ -150,3 +150,1357 @@ This      SHOULD         ignore        whitespace.
 
     Ok(())
 }
+
+#[test]
+fn report_schema() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be described.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=test
+//# This MUST be described.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json");
+    let schema = env.path("target/report.schema.json");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--json",
+        &out.display().to_string(),
+        "--label",
+        "platform=linux",
+        "--label",
+        "suite=nightly",
+    ])?;
+    env.exec(["schema", "--out", &schema.display().to_string()])?;
+
+    let out = env.get_json(&out)?;
+    let schema = env.get_json(&schema)?;
+
+    assert_eq!(out["labels"]["platform"], "linux");
+    assert_eq!(out["labels"]["suite"], "nightly");
+
+    // every emitted field should be documented by the schema
+    let properties = &schema["properties"];
+    for key in out.as_object().unwrap().keys() {
+        assert!(properties.get(key).is_some(), "missing {:?}", key);
+    }
+
+    let properties = &schema["definitions"]["annotation"]["properties"];
+    for annotation in out["annotations"].as_array().unwrap() {
+        for key in annotation.as_object().unwrap().keys() {
+            assert!(properties.get(key).is_some(), "missing {:?}", key);
+        }
+    }
+
+    let err = env
+        .exec([
+            "report",
+            "--source-pattern",
+            &code,
+            "--json",
+            &env.path("target/report.json").display().to_string(),
+            "--label",
+            "platform=linux",
+            "--label",
+            "platform=macos",
+        ])
+        .unwrap_err();
+    assert!(err.to_string().contains("duplicate label \"platform\""));
+
+    Ok(())
+}
+
+#[test]
+fn exclude_pattern() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be cited.
+        "#,
+    )?;
+
+    env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//# This MUST be cited.
+            "#
+        ),
+    )?;
+
+    // fixtures that look like annotations but reference a missing section
+    env.put(
+        "src/fixtures/data.rs",
+        format!(
+            r#"
+//= {spec}#missing
+//# This does not exist.
+            "#
+        ),
+    )?;
+
+    let sources = env.path("src/**/*.rs").display().to_string();
+    let out = env.path("target/report.json").display().to_string();
+
+    assert!(env
+        .exec(["report", "--source-pattern", &sources, "--json", &out])
+        .is_err());
+
+    let exclude = env.path("src/fixtures/**").display().to_string();
+    env.exec([
+        "report",
+        "--source-pattern",
+        &sources,
+        "--exclude-pattern",
+        &exclude,
+        "--json",
+        &out,
+    ])?;
+
+    let out = env.get_json(&out)?;
+    assert_eq!(out["annotations"].as_array().unwrap().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn line_anchor() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "notes.txt",
+        r#"Design notes
+
+The client MUST retry
+at most three times.
+
+The server SHOULD log retries.
+"#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#L3-L4
+//# The client MUST retry at most three times.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--json",
+        &out.display().to_string(),
+    ])?;
+
+    let out = env.get_json(&out)?;
+    let section = &out["specifications"][&spec]["sections"][0];
+    assert_eq!(section["id"], "L3-L4");
+    assert_eq!(section["title"], "Lines 3-4");
+    assert_eq!(section["lines"][1][0][2], "at most three times.");
+
+    let report = || {
+        env.exec([
+            "report",
+            "--source-pattern",
+            &code,
+            "--json",
+            &env.path("target/report.json").display().to_string(),
+        ])
+    };
+
+    // citations should break when the referenced lines move
+    env.put(
+        "notes.txt",
+        r#"Design notes
+
+Revised on 2024-01-01.
+The client MUST retry
+at most three times.
+
+The server SHOULD log retries.
+"#,
+    )?;
+
+    let err = report().unwrap_err();
+    assert!(err.to_string().contains("no reports were generated"));
+
+    // without a quote there's nothing to compare the lines against
+    env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#L3-L4
+            "#
+        ),
+    )?;
+
+    let err = report().unwrap_err();
+    assert!(err.to_string().contains("no reports were generated"));
+
+    Ok(())
+}
+
+#[test]
+fn man_page() -> Result {
+    let env = Env::new()?;
+
+    let out = env.path("duvet.1");
+    env.exec(["man", "--out", &out.display().to_string()])?;
+
+    let page = env.get(&out)?;
+    assert!(page.starts_with(".TH DUVET 1"));
+    assert!(page.contains(".SH \"DUVET REPORT\""));
+    assert!(page.contains("--source-pattern"));
+
+    Ok(())
+}
+
+#[test]
+fn extract_single_file() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# My spec
+
+## Testing
+
+This quote MUST work.
+Another SHOULD work too.
+        "#,
+    )?;
+
+    let out = env.path("requirements.json");
+    env.exec([
+        "extract",
+        "--format",
+        "markdown",
+        "--out",
+        &out.display().to_string(),
+        &spec,
+    ])?;
+
+    let out = env.get_json(&out)?;
+    let section = &out[0];
+    assert_eq!(section["target"], format!("{}#testing", spec));
+    assert_eq!(section["title"], "Testing");
+    assert_eq!(section["requirements"][0]["level"], "MUST");
+    assert_eq!(section["requirements"][0]["quote"], "This quote MUST work.");
+    assert_eq!(section["requirements"][1]["level"], "SHOULD");
+
+    let out = env.path("requirements.toml");
+    env.exec([
+        "extract",
+        "--format",
+        "markdown",
+        "--out",
+        &out.display().to_string(),
+        &spec,
+    ])?;
+
+    let toml = env.get(&out)?;
+    assert!(toml.contains(&format!("target = \"{}#testing\"", spec)));
+
+    // the single file should be usable as a spec file
+    let report = env.path("target/report.json");
+    env.exec([
+        "report",
+        "--spec-pattern",
+        &out.display().to_string(),
+        "--json",
+        &report.display().to_string(),
+    ])?;
+
+    let report = env.get_json(&report)?;
+    assert_eq!(report["annotations"].as_array().unwrap().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn evidence() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be cited. This SHOULD be tested.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//= level=MUST
+//# This MUST be cited.
+
+//= {spec}#testing
+//= type=spec
+//= level=SHOULD
+//# This SHOULD be tested.
+
+//= {spec}#testing
+//# This MUST be cited.
+fn cited() {{}}
+
+//= {spec}#testing
+//= type=test
+//# This MUST be cited.
+fn tested() {{}}
+            "#
+        ),
+    )?;
+
+    let dir = env.path("target/evidence");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--evidence",
+        &dir.display().to_string(),
+    ])?;
+
+    let files: Vec<_> = std::fs::read_dir(&dir)?
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+
+    // only the fulfilled requirement has evidence
+    assert_eq!(files.len(), 2, "{:?}", files);
+
+    let json = files.iter().find(|name| name.ends_with(".json")).unwrap();
+    let json = env.get_json(dir.join(json))?;
+    assert_eq!(json["level"], "MUST");
+    assert_eq!(json["evidence"].as_array().unwrap().len(), 2);
+
+    let markdown = files.iter().find(|name| name.ends_with(".md")).unwrap();
+    let markdown = env.get(dir.join(markdown))?;
+    assert!(markdown.contains("## Citations"));
+    assert!(markdown.contains("## Tests"));
+
+    Ok(())
+}
+
+#[test]
+fn restrict_exceptions() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be implemented. This SHOULD be implemented.
+        "#,
+    )?;
+
+    let should = env.put(
+        "src/should.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//= level=SHOULD
+//# This SHOULD be implemented.
+
+//= {spec}#testing
+//= type=exception
+//= reason=Not needed yet
+//# This SHOULD be implemented.
+            "#
+        ),
+    )?;
+
+    let must = env.put(
+        "src/must.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//= level=MUST
+//# This MUST be implemented.
+
+//= {spec}#testing
+//= type=exception
+//= reason=Not needed yet
+//# This MUST be implemented.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json").display().to_string();
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &should,
+        "--restrict-exceptions",
+        "--json",
+        &out,
+    ])?;
+
+    env.exec(["report", "--source-pattern", &must, "--json", &out])?;
+
+    assert!(env
+        .exec([
+            "report",
+            "--source-pattern",
+            &must,
+            "--restrict-exceptions",
+            "--json",
+            &out,
+        ])
+        .is_err());
+
+    // without a level, the key words in the quote are used
+    let auto = env.put(
+        "src/auto.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//# This MUST be implemented.
+
+//= {spec}#testing
+//= type=exception
+//= reason=Not needed yet
+//# This MUST be implemented.
+            "#
+        ),
+    )?;
+
+    assert!(env
+        .exec([
+            "report",
+            "--source-pattern",
+            &auto,
+            "--restrict-exceptions",
+            "--json",
+            &out,
+        ])
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn coverage_thresholds() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be cited. This MUST also be cited.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//# This MUST be cited.
+
+//= {spec}#testing
+//= type=spec
+//# This MUST also be cited.
+
+//= {spec}#testing
+//# This MUST be cited.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json").display().to_string();
+    let report = |args: &[&str]| {
+        let mut all = vec!["report", "--source-pattern", &code, "--json", &out];
+        all.extend_from_slice(args);
+        env.exec(all)
+    };
+
+    // no reports are written in summary-only mode
+    report(&["--summary-only", "--quiet"])?;
+    assert!(!env.path("target/report.json").exists());
+
+    report(&["--summary", "--min-citation-coverage", "50"])?;
+    assert!(env.path("target/report.json").exists());
+
+    let err = report(&["--min-citation-coverage", "75"]).unwrap_err();
+    assert!(err.to_string().contains("citation coverage 50.0%"));
+
+    let err = report(&["--min-test-coverage", "1"]).unwrap_err();
+    assert!(err.to_string().contains("test coverage 0.0%"));
+
+    Ok(())
+}
+
+#[test]
+fn baseline() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be cited. This MUST be done later.
+        "#,
+    )?;
+
+    let requirements = format!(
+        r#"
+//= {spec}#testing
+//= type=spec
+//# This MUST be cited.
+
+//= {spec}#testing
+//= type=spec
+//# This MUST be done later.
+        "#
+    );
+
+    let citation = format!(
+        r#"
+//= {spec}#testing
+//# This MUST be cited.
+        "#
+    );
+
+    let todo = format!(
+        r#"
+//= {spec}#testing
+//= type=todo
+//# This MUST be done later.
+        "#
+    );
+
+    let exception = format!(
+        r#"
+//= {spec}#testing
+//= type=exception
+//= reason=Not needed
+//# This MUST be done later.
+        "#
+    );
+
+    let code = env.path("src/my-code.rs").display().to_string();
+    let baseline = env.path("target/baseline.json").display().to_string();
+    let out = env.path("target/report.json").display().to_string();
+
+    env.put("src/my-code.rs", format!("{requirements}{citation}{todo}"))?;
+    env.exec(["report", "--source-pattern", &code, "--json", &baseline])?;
+
+    let report = || {
+        env.exec([
+            "report",
+            "--source-pattern",
+            &code,
+            "--json",
+            &out,
+            "--baseline",
+            &baseline,
+        ])
+    };
+
+    // resolving a TODO isn't a regression
+    env.put("src/my-code.rs", format!("{requirements}{citation}"))?;
+    report()?;
+
+    // removing the citation uncovers a requirement
+    env.put("src/my-code.rs", format!("{requirements}{todo}"))?;
+    let err = report().unwrap_err();
+    assert!(err.to_string().contains("1 regressions"));
+
+    // replacing the TODO with an exception
+    env.put(
+        "src/my-code.rs",
+        format!("{requirements}{citation}{exception}"),
+    )?;
+    let err = report().unwrap_err();
+    assert!(err.to_string().contains("1 regressions"));
+
+    Ok(())
+}
+
+#[test]
+fn graph() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be cited. This MUST be tested.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//# This MUST be cited.
+
+//= {spec}#testing
+//# This MUST be cited.
+
+//= {spec}#testing
+//= type=test
+//# This MUST be tested.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/graph.dot");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--graph",
+        &out.display().to_string(),
+    ])?;
+
+    let graph = env.get(&out)?;
+    assert!(graph.starts_with("digraph duvet {"));
+    assert!(graph.contains("shape=folder"));
+    assert!(graph.contains("shape=note"));
+    // the test isn't related to a requirement
+    assert_eq!(graph.matches("[style=dashed]").count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn compliance_manifest_and_badge() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be cited. This MUST be tested.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//# This MUST be cited.
+
+//= {spec}#testing
+//= type=spec
+//# This MUST be tested.
+
+//= {spec}#testing
+//# This MUST be cited.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/manifest.json");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--manifest",
+        &out.display().to_string(),
+        "--badge",
+        &env.path("target/badge").display().to_string(),
+    ])?;
+
+    let badge = env.get_json("target/badge/badge.json")?;
+    assert_eq!(badge["message"], "50%");
+    assert_eq!(badge["color"], "yellow");
+    assert!(env
+        .get("target/badge/badge.svg")?
+        .contains("<title>compliance: 50%</title>"));
+
+    let spec_path = spec;
+    let manifest = env.get_json(&out)?;
+    assert_eq!(manifest["manifestVersion"], 1);
+    assert_eq!(manifest["tool"]["name"], "duvet");
+
+    let spec = &manifest["specifications"][0];
+    assert_eq!(spec["format"], "markdown");
+    // sha256 of the spec file, as printed by `sha256sum`
+    assert_eq!(
+        spec["digest"],
+        "sha256:d5eb6e575314c1808aecfa8c7d50e41c9131d4094d43e422e950be3321cecd31"
+    );
+    assert_eq!(spec["requirements"], 2);
+    assert_eq!(spec["fulfilled"], 50.0);
+
+    // without any requirements there isn't a meaningful percentage
+    env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec_path}#testing
+//# This MUST be cited.
+            "#
+        ),
+    )?;
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--manifest",
+        &out.display().to_string(),
+        "--badge",
+        &env.path("target/badge").display().to_string(),
+    ])?;
+
+    let badge = env.get_json("target/badge/badge.json")?;
+    assert_eq!(badge["message"], "n/a");
+    assert_eq!(badge["color"], "lightgrey");
+
+    let manifest = env.get_json(&out)?;
+    let spec = &manifest["specifications"][0];
+    assert_eq!(spec["requirements"], 0);
+    assert!(spec["fulfilled"].is_null());
+
+    Ok(())
+}
+
+#[test]
+fn spec_excerpts() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be cited.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//# This MUST be cited.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json").display().to_string();
+
+    let err = env
+        .exec(["report", "--source-pattern", &code, "--json", &out, "--ci"])
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("missing citation"));
+    assert!(message.contains("--> # Testing"));
+    assert!(message.contains("| This MUST be cited."));
+
+    Ok(())
+}
+
+#[test]
+fn strict() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be cited.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}
+//# This MUST be cited.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json").display().to_string();
+
+    env.exec(["report", "--source-pattern", &code, "--json", &out])?;
+
+    assert!(env
+        .exec([
+            "report",
+            "--source-pattern",
+            &code,
+            "--json",
+            &out,
+            "--strict"
+        ])
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn exception_expiry() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be implemented.
+        "#,
+    )?;
+
+    let exception = |expires: &str| {
+        format!(
+            r#"
+//= {spec}#testing
+//= type=exception
+//= reason=Waiting on a dependency
+//= expires={expires}
+//# This MUST be implemented.
+            "#
+        )
+    };
+
+    let code = env.path("src/my-code.rs").display().to_string();
+    let out = env.path("target/report.json").display().to_string();
+    let report = || env.exec(["report", "--source-pattern", &code, "--json", &out]);
+
+    env.put("src/my-code.rs", exception("9999-12-31"))?;
+    report()?;
+
+    let json = env.get_json("target/report.json")?;
+    let excepted = json["annotations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|annotation| annotation["type"] == "EXCEPTION")
+        .unwrap();
+    assert_eq!(excepted["expires"], "9999-12-31");
+
+    env.put("src/my-code.rs", exception("2000-01-01"))?;
+    let err = report().unwrap_err();
+    assert!(err.to_string().contains("expired exceptions"));
+
+    env.put("src/my-code.rs", exception("next week"))?;
+    let err = report().unwrap_err();
+    assert!(format!("{:?}", err).contains("invalid date"));
+
+    // tags that happen to look like an expiry are left alone
+    env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=exception
+//= tag=expires:2000-01-01
+//# This MUST be implemented.
+            "#
+        ),
+    )?;
+    report()?;
+
+    env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=exception
+//= expires=9999-12-31
+//= expires=2000-01-01
+//# This MUST be implemented.
+            "#
+        ),
+    )?;
+    let err = report().unwrap_err();
+    assert!(format!("{:?}", err).contains("expires already specified"));
+
+    Ok(())
+}
+
+#[test]
+fn metrics() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be implemented.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//= metric=3
+//# This MUST be implemented.
+
+//= {spec}#testing
+//= type=todo
+//= metric=5
+//# This MUST be implemented.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--json",
+        &out.display().to_string(),
+    ])?;
+
+    let report = || {
+        env.exec([
+            "report",
+            "--source-pattern",
+            &code,
+            "--json",
+            &out.display().to_string(),
+        ])
+    };
+
+    let json = env.get_json(&out)?;
+    let statuses = json["statuses"].as_object().unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses.values().next().unwrap()["metric"], 8);
+
+    // metrics are kept separate from tags
+    for annotation in json["annotations"].as_array().unwrap() {
+        assert!(annotation.get("tags").is_none());
+    }
+
+    env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//= metric=5
+//= tag=metric:1000
+//# This MUST be implemented.
+            "#
+        ),
+    )?;
+    report()?;
+    let json = env.get_json(&out)?;
+    let statuses = json["statuses"].as_object().unwrap();
+    assert_eq!(statuses.values().next().unwrap()["metric"], 5);
+
+    env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//= metric=5
+//= metric=3
+//# This MUST be implemented.
+            "#
+        ),
+    )?;
+    let err = report().unwrap_err();
+    assert!(format!("{:?}", err).contains("metric already specified"));
+
+    Ok(())
+}
+
+#[test]
+fn require_version() -> Result {
+    let env = Env::new()?;
+    let out = env.path("target/report.schema.json").display().to_string();
+
+    env.exec(["--require-version", ">=0.1", "schema", "--out", &out])?;
+    env.exec(["schema", "--out", &out, "--require-version", "<1000"])?;
+
+    // partial versions match any patch release
+    let current: Vec<_> = env!("CARGO_PKG_VERSION").split('.').collect();
+    let minor = format!("{}.{}", current[0], current[1]);
+    env.exec(["--require-version", &minor, "schema", "--out", &out])?;
+    env.exec([
+        "--require-version",
+        &format!("={}", minor),
+        "schema",
+        "--out",
+        &out,
+    ])?;
+
+    let err = env
+        .exec(["--require-version", ">=1000", "schema", "--out", &out])
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("does not satisfy the required version"));
+
+    Ok(())
+}
+
+#[test]
+fn junit() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST pass. This MUST fail. This MAY be skipped.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//= type=spec
+//# This MUST pass.
+
+//= {spec}#testing
+//= type=spec
+//# This MUST fail.
+
+//= {spec}#testing
+//= type=spec
+//# This MAY be skipped.
+
+//= {spec}#testing
+//# This MUST pass.
+
+//= {spec}#testing
+//= type=test
+//# This MUST pass.
+
+//= {spec}#testing
+//# This MUST fail.
+
+//= {spec}#testing
+//= type=exception
+//= reason=Not <applicable>
+//# This MAY be skipped.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/junit.xml");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--junit",
+        &out.display().to_string(),
+    ])?;
+
+    let xml = env.get(&out)?;
+    assert!(xml.contains(r#"tests="3" failures="1" skipped="1""#));
+    assert!(xml.contains(r#"name="testing: This MUST pass."/>"#));
+    assert!(xml.contains(r#"<failure message="missing test"/>"#));
+    assert!(xml.contains(r#"<skipped message="Not &lt;applicable&gt;"/>"#));
+
+    Ok(())
+}
+
+#[test]
+fn quote_mismatch_non_ascii() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+The client “MUST” reject café messages.
+        "#,
+    )?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//# "MUST" reject cafe
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json").display().to_string();
+    let err = env
+        .exec(["report", "--source-pattern", &code, "--json", &out])
+        .unwrap_err();
+    assert!(err.to_string().contains("source errors were found"));
+
+    Ok(())
+}
+
+#[test]
+fn languages() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "my-spec.md",
+        r#"
+# Testing
+
+This MUST be implemented.
+        "#,
+    )?;
+
+    env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#testing
+//# This MUST be implemented.
+fn implemented() {{}}
+"#
+        ),
+    )?;
+    env.put(
+        "src/other.rs",
+        "fn other() {}
+",
+    )?;
+    env.put("src/script.py", "print('hi')\nprint('bye')\n")?;
+    env.put("src/util.c", "int util() { return 0; }\n")?;
+    env.put("src/util.h", "int util();\n")?;
+    let specs = env.put(
+        "specs.toml",
+        format!(
+            r#"
+target = "{spec}#testing"
+
+[[spec]]
+quote = "This MUST be implemented."
+"#
+        ),
+    )?;
+
+    let out = env.path("target/report.json");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &env.path("src/**/*.rs").display().to_string(),
+        "--source-pattern",
+        &env.path("src/**/*.py").display().to_string(),
+        "--source-pattern",
+        &env.path("src/**/*.[ch]").display().to_string(),
+        "--spec-pattern",
+        &specs,
+        "--json",
+        &out.display().to_string(),
+    ])?;
+
+    let json = env.get_json(&out)?;
+    let languages = &json["languages"];
+
+    assert_eq!(languages["Rust"]["files"], 2);
+    assert_eq!(languages["Rust"]["annotated_files"], 1);
+    assert_eq!(languages["Rust"]["lines"], 5);
+    assert_eq!(languages["Rust"]["annotations"], 1);
+
+    assert_eq!(languages["Python"]["files"], 1);
+    assert_eq!(languages["Python"]["annotated_files"], 0);
+    assert_eq!(languages["Python"]["lines"], 2);
+    assert_eq!(languages["Python"]["annotations"], 0);
+
+    // headers are counted with their language
+    assert_eq!(languages["C"]["files"], 2);
+
+    // spec files aren't source code
+    assert!(languages.get("TOML").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn protobuf() -> Result {
+    let env = Env::new()?;
+
+    let spec = env.put(
+        "api.proto",
+        r#"
+syntax = "proto3";
+
+message A {
+  // A's status MUST be set.
+  enum Status {
+    OK = 0;
+  }
+}
+
+message B {
+  // B's status MAY be empty.
+  enum Status {
+    OK = 0;
+  }
+}
+"#,
+    )?;
+
+    // the format is picked from the `.proto` extension
+    let code = env.put(
+        "src/my-code.rs",
+        format!(
+            r#"
+//= {spec}#A.Status
+//# A's status MUST be set.
+
+//= {spec}#B.Status
+//# B's status MAY be empty.
+            "#
+        ),
+    )?;
+
+    let out = env.path("target/report.json");
+
+    env.exec([
+        "report",
+        "--source-pattern",
+        &code,
+        "--json",
+        &out.display().to_string(),
+    ])?;
+
+    let out = env.get_json(&out)?;
+    assert_eq!(out["specifications"][&spec]["format"], "protobuf");
+
+    Ok(())
+}
+
+#[test]
+fn invalid_annotation() -> Result {
+    let env = Env::new()?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        r#"
+//= my-spec.md#testing
+//= type=unknown
+//# This is not a valid annotation
+        "#,
+    )?;
+
+    let err = env
+        .exec([
+            "report",
+            "--source-pattern",
+            &code,
+            "--json",
+            &env.path("target/report.json").display().to_string(),
+        ])
+        .unwrap_err();
+
+    let message = format!("{:?}", err);
+    assert!(message.contains("could not extract annotations from"));
+    assert!(message.contains("Invalid annotation type"));
+
+    Ok(())
+}
+
+#[test]
+fn offline_specs() -> Result {
+    let env = Env::new()?;
+
+    let code = env.put(
+        "src/my-code.rs",
+        r#"
+//= https://example.com/my-spec.md#testing
+//# This MUST work offline.
+        "#,
+    )?;
+
+    let spec_path = env.path("cache").display().to_string();
+    let out = env.path("target/report.json").display().to_string();
+    let args = [
+        "report",
+        "--source-pattern",
+        &code,
+        "--spec-path",
+        &spec_path,
+        "--offline",
+        "--json",
+        &out,
+    ];
+
+    // nothing is cached yet
+    let err = env.exec(args).unwrap_err();
+    assert!(format!("{:?}", err).contains("--offline was set"));
+
+    env.put(
+        "cache/specs/example.com/my-spec.txt",
+        r#"
+# Testing
+
+This MUST work offline.
+        "#,
+    )?;
+
+    env.exec(args)?;
+
+    let out = env.get_json(&out)?;
+    assert!(out["specifications"]
+        .get("https://example.com/my-spec.md")
+        .is_some());
+
+    Ok(())
+}
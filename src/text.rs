@@ -24,9 +24,52 @@ fn fast_find(needle: &str, haystack: &str) -> Option<Range<usize>> {
 }
 
 fn slow_find(needle: &str, haystack: &str) -> Option<Range<usize>> {
+    normalized_find(needle, haystack, fast_find)
+}
+
+/// Returns the range in `haystack` that most closely resembles `needle`
+///
+/// This is used for diagnostics when a quote has drifted from the specification text. At least
+/// half of the `needle` needs to match in order to be considered. The returned range is widened
+/// to whole words.
+pub fn closest(needle: &str, haystack: &str) -> Option<Range<usize>> {
+    let range = normalized_find(needle, haystack, |needle, haystack| {
+        text_search(needle.as_bytes(), haystack.as_bytes())
+            .min_by_key(|m| (m.k, m.start))
+            .map(|m| m.start..m.end)
+    })?;
+
+    // the search operates on bytes so the match can start or end inside of a word or even a
+    // multi-byte character
+    let bytes = haystack.as_bytes();
+    let is_word_start = |idx: usize| {
+        idx == 0 || haystack.is_char_boundary(idx) && bytes[idx - 1].is_ascii_whitespace()
+    };
+    let is_word_end = |idx: usize| {
+        idx == bytes.len() || haystack.is_char_boundary(idx) && bytes[idx].is_ascii_whitespace()
+    };
+
+    let mut start = range.start;
+    while !is_word_start(start) {
+        start -= 1;
+    }
+
+    let mut end = range.end.max(start);
+    while !is_word_end(end) {
+        end += 1;
+    }
+
+    Some(start..end)
+}
+
+fn normalized_find<F: Fn(&str, &str) -> Option<Range<usize>>>(
+    needle: &str,
+    haystack: &str,
+    find: F,
+) -> Option<Range<usize>> {
     let (needle, _) = normalize_whitespace(needle);
     let (haystack, offset_map) = normalize_whitespace(haystack);
-    let range = fast_find(&needle, &haystack)?;
+    let range = find(&needle, &haystack)?;
 
     let start = offset_map[range.start];
     let end = offset_map[range.end];
@@ -102,4 +145,32 @@ mod tests {
         "this is a new-\nline",
         "this is a new-line"
     );
+
+    fn closest<'a>(needle: &str, haystack: &'a str) -> Option<&'a str> {
+        super::closest(needle, haystack).map(|r| &haystack[r])
+    }
+
+    #[test]
+    fn closest_test() {
+        let haystack = "Implementations MUST reject\n   messages that are too large. Other text.";
+
+        assert_eq!(
+            closest(
+                "implementations must reject messages that are large",
+                haystack
+            ),
+            Some("Implementations MUST reject\n   messages that are too large.")
+        );
+        assert_eq!(closest("something else entirely", haystack), None);
+    }
+
+    #[test]
+    fn closest_non_ascii_test() {
+        let haystack = "The client “MUST” reject café messages.";
+
+        assert_eq!(
+            closest("\"MUST\" reject cafe", haystack),
+            Some("“MUST” reject café")
+        );
+    }
 }
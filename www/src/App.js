@@ -4,10 +4,10 @@ import CssBaseline from "@material-ui/core/CssBaseline";
 import Container from "@material-ui/core/Container";
 import { Switch, Route, useParams } from "react-router-dom";
 import { Nav } from "./nav";
-import { Spec, Stats } from "./spec";
+import { Languages, Spec, Stats } from "./spec";
 import { Section } from "./section";
 import { Link } from "./link";
-import specifications from "./result";
+import { default as specifications, languages } from "./result";
 import clsx from "clsx";
 
 const drawerWidth = 400;
@@ -105,16 +105,26 @@ function App() {
 }
 
 function Main() {
-  return specifications
-    .filter((spec) => spec.stats.overall.total)
-    .map((spec) => (
-      <div key={spec.id}>
-        <Link to={spec.url}>
-          <h2>{spec.title}</h2>
-        </Link>
-        <Stats spec={spec} />
-      </div>
-    ));
+  return (
+    <>
+      {specifications
+        .filter((spec) => spec.stats.overall.total)
+        .map((spec) => (
+          <div key={spec.id}>
+            <Link to={spec.url}>
+              <h2>{spec.title}</h2>
+            </Link>
+            <Stats spec={spec} />
+          </div>
+        ))}
+      {Object.keys(languages).length ? (
+        <>
+          <h2>Languages</h2>
+          <Languages languages={languages} />
+        </>
+      ) : null}
+    </>
+  );
 }
 
 function SpecRoute() {
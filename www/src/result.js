@@ -148,6 +148,7 @@ function getRequirementsStats(reqs) {
   reqs.maxFeatures = 0;
   reqs.maxTrackingIssues = 0;
   reqs.maxTags = 0;
+  reqs.hasMetric = false;
 
   reqs.forEach((requirement) => {
     stats.overall.onRequirement(requirement);
@@ -182,6 +183,8 @@ function getRequirementsStats(reqs) {
     requirement.tags = Array.from(tags);
     requirement.tags.sort();
     reqs.maxTags = Math.max(reqs.maxTags, tags.size);
+
+    reqs.hasMetric = reqs.hasMetric || requirement.metric !== undefined;
   });
 
   return stats;
@@ -287,4 +290,6 @@ function mapLine(line) {
   });
 }
 
+export const languages = input.languages || {};
+
 export default specifications;
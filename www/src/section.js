@@ -280,7 +280,10 @@ function AnnotationRef({ title, alt, refs, expanded }) {
 
   const content = refs.length ? (
     refs.map((anno, id) => {
-      const text = <ListItemText secondary={anno.source.title} />;
+      const secondary = anno.expires
+        ? `${anno.source.title} (expires ${anno.expires})`
+        : anno.source.title;
+      const text = <ListItemText secondary={secondary} />;
       const content = anno.source.href ? (
         <Link href={anno.source.href}>{text}</Link>
       ) : (
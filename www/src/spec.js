@@ -178,6 +178,14 @@ export function Requirements({ requirements, showSection }) {
       headerName: requirements.maxTags === 1 ? "Tag" : "Tags",
     });
 
+  if (requirements.hasMetric)
+    columns.push({
+      field: "metric",
+      headerName: "Metric",
+      type: "number",
+      width: 100,
+    });
+
   columns.push({
     field: "comment",
     headerName: "Text",
@@ -239,6 +247,39 @@ export function Stats({ spec: { stats } }) {
   );
 }
 
+export function Languages({ languages }) {
+  const names = Object.keys(languages);
+  if (!names.length) return null;
+
+  return (
+    <Table size="small">
+      <TableHead>
+        <TableRow>
+          <TableCell component="th">Language</TableCell>
+          <TableCell align="right">Files</TableCell>
+          <TableCell align="right">Annotated files</TableCell>
+          <TableCell align="right">Lines</TableCell>
+          <TableCell align="right">Annotations</TableCell>
+        </TableRow>
+      </TableHead>
+      <TableBody>
+        {names.map((name) => {
+          const language = languages[name];
+          return (
+            <TableRow key={name}>
+              <TableCell component="th">{name}</TableCell>
+              <TableCell align="right">{language.files}</TableCell>
+              <TableCell align="right">{language.annotated_files}</TableCell>
+              <TableCell align="right">{language.lines}</TableCell>
+              <TableCell align="right">{language.annotations}</TableCell>
+            </TableRow>
+          );
+        })}
+      </TableBody>
+    </Table>
+  );
+}
+
 function StatsRow({ title, stats, ...props }) {
   return (
     <TableRow {...props}>